@@ -31,8 +31,7 @@ pub fn edit_input(
     let editor: String = match env::var("EDITOR") {
         Ok(editor) if check_installed(&editor) => editor,
         Ok(_) => {
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
+            return Err(Box::new(std::io::Error::other(
                 "$EDITOR set but editor not installed",
             )))
         }
@@ -51,8 +50,7 @@ pub fn edit_input(
     let status = Command::new(editor).arg(temp_file.path()).status()?;
 
     if !status.success() {
-        return Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
+        return Err(Box::new(std::io::Error::other(
             "Editor returned non-zero exit status",
         )));
     }
@@ -0,0 +1,85 @@
+use advent_of_utils_cli::{
+    error::{AocError, DatabaseError, OutputError},
+    types::{display::Table, AocCache, AocCacheArchive, AocInputReport, InputStatus},
+};
+use std::fs;
+
+use crate::config::CacheConfig;
+
+pub fn run(config: &CacheConfig) -> Result<(), AocError> {
+    match config {
+        CacheConfig::List { year, database } => {
+            let cache = AocCache::new(database.list_cache(*year)?);
+            match cache.is_empty() {
+                true => println!("Nothing is cached for profile {}", database.profile()),
+                false => cache.table(),
+            }
+        }
+        CacheConfig::Clear {
+            year,
+            day,
+            answers,
+            database,
+        } => {
+            let cleared = database.clear_cache(*year, *day, *answers)?;
+            println!(
+                "Removed {} inputs and answer sets of profile {}",
+                cleared,
+                database.profile()
+            );
+        }
+        CacheConfig::Verify {
+            year,
+            rehash,
+            database,
+        } => {
+            let report = AocInputReport::new(database.verify_inputs(*year, *rehash)?);
+            report.table();
+            if report.count(InputStatus::Unhashed) > 0 {
+                println!("Run 'aou cache verify --rehash' to store hashes for the unhashed inputs");
+            }
+            if report.count(InputStatus::Mismatch) > 0 {
+                println!("Run 'aou cache verify --rehash' if you changed the inputs on purpose");
+            }
+            match report.count(InputStatus::Mismatch) {
+                0 => {}
+                count => return Err(AocError::Database(DatabaseError::IntegrityFailed { count })),
+            }
+        }
+        CacheConfig::Normalize {
+            year,
+            day,
+            normalization,
+            database,
+        } => {
+            database.set_normalization(*year, *day, *normalization)?;
+            println!(
+                "Input normalization of {} day {}: {}",
+                year, day, normalization
+            );
+        }
+        CacheConfig::Export { file, database } => {
+            let archive = serde_json::to_string_pretty(&database.export_cache()?)?;
+            fs::write(file, archive).map_err(|source| {
+                AocError::Output(OutputError::WriteFailed {
+                    path: file.clone(),
+                    source,
+                })
+            })?;
+            println!("Exported the cache to {}", file.display());
+        }
+        CacheConfig::Import { file, database } => {
+            let archive = fs::read_to_string(file).map_err(|source| {
+                AocError::Output(OutputError::ReadFailed {
+                    path: file.clone(),
+                    source,
+                })
+            })?;
+            let archive: AocCacheArchive = serde_json::from_str(&archive)?;
+            let imported = database.import_cache(&archive)?;
+            println!("Imported {} entries from {}", imported, file.display());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use std::str::FromStr;
+
+/// Days picked on the command line as a comma separated list of days and ranges, e.g.
+/// `1-5,9,12-`. A range without an end runs up to the latest available day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaySelection {
+    /// Inclusive ranges, `None` as end for open ranges
+    ranges: Vec<(u8, Option<u8>)>,
+}
+
+impl DaySelection {
+    pub fn single(day: u8) -> Self {
+        Self {
+            ranges: vec![(day, Some(day))],
+        }
+    }
+
+    /// The day if exactly one is selected
+    pub fn as_single(&self) -> Option<u8> {
+        match self.ranges.as_slice() {
+            [(start, Some(end))] if start == end => Some(*start),
+            _ => None,
+        }
+    }
+
+    /// Days named explicitly, i.e. all starts and ends of the ranges
+    pub fn bounds(&self) -> impl Iterator<Item = u8> + '_ {
+        self.ranges
+            .iter()
+            .flat_map(|(start, end)| std::iter::once(*start).chain(*end))
+    }
+
+    /// All selected days in order, open ranges end at `last`
+    pub fn days(&self, last: u8) -> Vec<u8> {
+        let mut days: Vec<u8> = self
+            .ranges
+            .iter()
+            .flat_map(|&(start, end)| start..=end.unwrap_or(last))
+            .collect();
+        days.sort_unstable();
+        days.dedup();
+        days
+    }
+}
+
+fn parse_day(day: &str) -> Result<u8, String> {
+    match day.trim().parse() {
+        Ok(0) | Err(_) => Err(format!("\"{}\" is not a day", day.trim())),
+        Ok(day) => Ok(day),
+    }
+}
+
+impl FromStr for DaySelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ranges = s
+            .split(',')
+            .map(|part| match part.split_once('-') {
+                None => parse_day(part).map(|day| (day, Some(day))),
+                Some((start, end)) if end.trim().is_empty() => {
+                    parse_day(start).map(|start| (start, None))
+                }
+                Some((start, end)) => {
+                    let (start, end) = (parse_day(start)?, parse_day(end)?);
+                    match start <= end {
+                        true => Ok((start, Some(end))),
+                        false => Err(format!("{start}-{end} ends before it starts")),
+                    }
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { ranges })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_days_and_ranges() {
+        let selection: DaySelection = "1-5,9, 12-,3".parse().unwrap();
+
+        assert_eq!(selection.days(14), vec![1, 2, 3, 4, 5, 9, 12, 13, 14]);
+        assert_eq!(
+            selection.bounds().collect::<Vec<_>>(),
+            vec![1, 5, 9, 9, 12, 3, 3]
+        );
+        assert_eq!(selection.as_single(), None);
+        assert_eq!("7".parse::<DaySelection>().unwrap().as_single(), Some(7));
+    }
+
+    #[test]
+    fn rejects_invalid_days() {
+        for invalid in ["", "0", "5-3", "a", "1,,2", "-4", "1-2-3"] {
+            assert!(invalid.parse::<DaySelection>().is_err(), "{invalid}");
+        }
+    }
+}
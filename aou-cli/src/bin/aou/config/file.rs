@@ -0,0 +1,203 @@
+use advent_of_utils_cli::{error::AocError, types::display::OutputFormat, types::DEFAULT_PROFILE};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Name of the project configuration, searched from the current directory upward
+pub const CONFIG_FILE: &str = "aou.toml";
+
+/// Settings of `[benchmark]`
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct BenchmarkSection {
+    /// Benchmark every run, like `--benchmark`
+    pub enabled: bool,
+    pub jobs: Option<u16>,
+    pub serial: bool,
+    pub threshold: Option<f64>,
+}
+
+/// Settings of a single day, e.g. `[days.12]`
+#[derive(Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct DaySection {
+    /// Seconds a part of the day may run, replacing the global timeout
+    pub timeout: Option<u64>,
+    /// `false` runs the day only once even when benchmarking
+    pub benchmark: Option<bool>,
+}
+
+/// Contents of `aou.toml`. Flags given on the command line take precedence over it
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Year used if none is given
+    pub year: Option<i32>,
+    workspace_dir: Option<PathBuf>,
+    /// Directory of the compiled solutions, `<workspace_dir>/target/release` if not set
+    library_dir: Option<PathBuf>,
+    pub format: Option<OutputFormat>,
+    profile: Option<String>,
+    /// Seconds a part may run before `aou` stops
+    pub timeout: Option<u64>,
+    pub benchmark: BenchmarkSection,
+    pub days: HashMap<u8, DaySection>,
+    /// Directory of the file, relative paths in it are resolved against it
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+impl ConfigFile {
+    /// Loads the first `aou.toml` in the directory or one of its parents.
+    /// Returns the defaults if there is none
+    pub fn discover(dir: &Path) -> Result<Self, AocError> {
+        match dir
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE))
+            .find(|path| path.is_file())
+        {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, AocError> {
+        let invalid = |reason: String| AocError::InvalidConfig {
+            path: path.to_path_buf(),
+            reason,
+        };
+        let content = fs::read_to_string(path).map_err(|error| invalid(error.to_string()))?;
+        let mut file: Self =
+            toml::from_str(&content).map_err(|error| invalid(error.to_string()))?;
+        file.dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        if file.benchmark.jobs == Some(0) {
+            return Err(invalid("benchmark.jobs has to be at least 1".to_string()));
+        }
+        Ok(file)
+    }
+
+    pub fn profile(&self, profile: Option<String>) -> String {
+        profile
+            .or_else(|| self.profile.clone())
+            .unwrap_or(DEFAULT_PROFILE.to_string())
+    }
+
+    /// The workspace given on the command line, else the one of the file. Defaults to the
+    /// directory of the file, or the current one without a file
+    pub fn workspace_dir(&self, workspace_dir: Option<PathBuf>) -> PathBuf {
+        match (workspace_dir, &self.workspace_dir) {
+            (Some(dir), _) => dir,
+            (None, Some(dir)) => self.dir.join(dir),
+            (None, None) if self.dir.as_os_str().is_empty() => PathBuf::from("."),
+            (None, None) => self.dir.clone(),
+        }
+    }
+
+    /// Directory the solution library is loaded from. A workspace given on the command line
+    /// wins over the `library_dir` of the file
+    pub fn library_dir(&self, workspace_dir: Option<PathBuf>) -> PathBuf {
+        match (&workspace_dir, &self.library_dir) {
+            (None, Some(dir)) => self.dir.join(dir),
+            _ => self
+                .workspace_dir(workspace_dir)
+                .join("target")
+                .join("release"),
+        }
+    }
+
+    pub fn timeout(&self, timeout: Option<u64>) -> Option<Duration> {
+        timeout.or(self.timeout).map(Duration::from_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn discovers_file_in_parent() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join(CONFIG_FILE),
+            r#"
+                year = 2023
+                workspace_dir = "solutions"
+                format = "json"
+                profile = "work"
+                timeout = 30
+
+                [benchmark]
+                enabled = true
+                jobs = 4
+
+                [days.12]
+                timeout = 300
+                benchmark = false
+            "#,
+        )
+        .unwrap();
+        let nested = dir.path().join("src").join("bin");
+        fs::create_dir_all(&nested).unwrap();
+
+        let file = ConfigFile::discover(&nested).unwrap();
+
+        assert_eq!(file.year, Some(2023));
+        assert_eq!(file.format, Some(OutputFormat::Json));
+        assert_eq!(file.profile(None), "work");
+        assert_eq!(
+            file.library_dir(None),
+            dir.path().join("solutions/target/release")
+        );
+        assert_eq!(
+            file.library_dir(Some(".".into())),
+            PathBuf::from("./target/release")
+        );
+        assert_eq!(file.timeout(None), Some(Duration::from_secs(30)));
+        assert!(file.benchmark.enabled);
+        assert_eq!(file.benchmark.jobs, Some(4));
+        assert_eq!(file.days[&12].timeout, Some(300));
+        assert_eq!(file.days[&12].benchmark, Some(false));
+    }
+
+    #[test]
+    fn workspace_defaults_to_directory_of_file() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(CONFIG_FILE), "year = 2023\n").unwrap();
+
+        let file = ConfigFile::discover(dir.path()).unwrap();
+
+        assert_eq!(file.workspace_dir(None), dir.path());
+        assert_eq!(
+            file.library_dir(None),
+            dir.path().join("target").join("release")
+        );
+    }
+
+    #[test]
+    fn defaults_without_file() {
+        let dir = TempDir::new().unwrap();
+        let file = ConfigFile::discover(dir.path()).unwrap();
+
+        assert_eq!(file.year, None);
+        assert_eq!(file.profile(None), DEFAULT_PROFILE);
+        assert_eq!(file.library_dir(None), PathBuf::from("./target/release"));
+        assert_eq!(file.timeout(None), None);
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(CONFIG_FILE), "yaer = 2023\n").unwrap();
+
+        assert!(matches!(
+            ConfigFile::discover(dir.path()),
+            Err(AocError::InvalidConfig { .. })
+        ));
+    }
+}
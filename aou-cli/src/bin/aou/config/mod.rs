@@ -1,23 +1,77 @@
-use advent_of_utils_cli::{error::AocError, types::AocDatabase, types::AocTime, Parts};
+use advent_of_utils_cli::{
+    error::AocError,
+    input::{read_input, SessionToken},
+    types::display::OutputFormat,
+    types::AocDatabase,
+    types::{AocStorage, EncryptedStorage, FileStorage, InputNormalization},
+    types::{AocTime, ShiftedClock, AOC_START_YEAR},
+    Parts,
+};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use std::fs::{read_dir, DirEntry};
+use std::sync::Arc;
 use std::time::Duration;
-use std::{error::Error, path::PathBuf};
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
 
-use crate::Cli;
+use crate::{loader, CacheCommand, Cli, Command, FilesArgs, RunArgs};
+
+mod days;
+mod file;
+
+pub use days::DaySelection;
+pub use file::{ConfigFile, DaySection};
+
+/// Slowdown in percent above which a part counts as a regression if none is configured
+const DEFAULT_THRESHOLD: f64 = 10.0;
+
+/// Passphrase for encrypting the cached inputs
+const PASSPHRASE_VAR: &str = "AOU_PASSPHRASE";
+
+/// RFC 3339 time `aou` starts at instead of the current one, e.g. to try `fetch --wait`
+const NOW_VAR: &str = "AOU_NOW";
 
 pub enum Config {
     Run(RunConfig),
     AddTest(AddTestConfig),
+    Fetch(FetchConfig),
+    Cache(CacheConfig),
+    Import(FilesConfig),
+    Export(FilesConfig),
+    Readme(ReadmeConfig),
+    Login(SessionConfig),
+    Logout(SessionConfig),
+    Whoami(SessionConfig),
+    Requests(RequestsConfig),
+    Leaderboard(LeaderboardConfig),
 }
 
 pub struct RunConfig {
     pub year: i32,
-    pub day: Option<u8>,
+    /// Days to run, all available ones if not set
+    pub days: Option<DaySelection>,
     pub part: Option<Parts>,
     pub test: bool,
     pub database: AocDatabase,
     pub workspace_dir: PathBuf,
+    pub session: Option<SessionToken>,
+    /// Input read from `--input` which replaces the cached one
+    pub input: Option<String>,
     pub benchmark: bool,
+    pub jobs: u16,
+    pub serial_benchmark: bool,
+    pub format: OutputFormat,
+    pub compare: bool,
+    pub baseline: Option<String>,
+    pub save_baseline: Option<String>,
+    pub threshold: f64,
+    /// Time a part may run before `aou` stops
+    pub timeout: Option<Duration>,
+    /// Settings of single days from `aou.toml`
+    pub day_settings: HashMap<u8, DaySection>,
 }
 
 pub struct AddTestConfig {
@@ -26,13 +80,159 @@ pub struct AddTestConfig {
     pub database: AocDatabase,
 }
 
-pub struct AddDayConfig {
+pub struct FetchConfig {
     pub year: i32,
-    pub day: u8,
+    pub day: Option<u8>,
+    pub wait: bool,
+    pub scaffold: bool,
+    pub open: bool,
     pub workspace_dir: PathBuf,
+    pub database: AocDatabase,
+    pub session: Option<SessionToken>,
+}
+
+pub enum CacheConfig {
+    List {
+        year: Option<i32>,
+        database: AocDatabase,
+    },
+    Clear {
+        year: Option<i32>,
+        day: Option<u8>,
+        answers: bool,
+        database: AocDatabase,
+    },
+    Verify {
+        year: Option<i32>,
+        rehash: bool,
+        database: AocDatabase,
+    },
+    Normalize {
+        year: i32,
+        day: u8,
+        normalization: InputNormalization,
+        database: AocDatabase,
+    },
+    Export {
+        file: PathBuf,
+        database: AocDatabase,
+    },
+    Import {
+        file: PathBuf,
+        database: AocDatabase,
+    },
+}
+
+pub struct FilesConfig {
+    pub year: Option<i32>,
+    pub pattern: String,
+    pub test: bool,
+    pub database: AocDatabase,
+}
+
+pub struct SessionConfig {
+    pub token: Option<SessionToken>,
+    pub database: AocDatabase,
+}
+
+pub struct RequestsConfig {
+    pub limit: usize,
+    pub database: AocDatabase,
+}
+
+pub struct LeaderboardConfig {
+    pub year: i32,
+    pub id: u64,
+    /// Day whose completion times are shown, the stars of all days if not set
+    pub day: Option<u8>,
+    pub database: AocDatabase,
+    pub session: Option<SessionToken>,
+}
+
+pub struct ReadmeConfig {
+    pub run: RunConfig,
+    pub file: PathBuf,
+    pub svg: Option<PathBuf>,
 }
 
 impl RunConfig {
+    fn from_args(
+        args: RunArgs,
+        test: bool,
+        database: AocDatabase,
+        file: &ConfigFile,
+    ) -> Result<Self, AocError> {
+        let workspace_dir = file.library_dir(args.workspace_dir);
+        let (year, days) = select_days(args.year, args.days, file, &workspace_dir, &AocTime::now());
+        if args.input.is_some() && days.as_ref().and_then(DaySelection::as_single).is_none() {
+            return Err(AocError::MissingDay {
+                flag: "--input".to_string(),
+            });
+        }
+        match &days {
+            Some(days) => days
+                .bounds()
+                .try_for_each(|day| AocTime::now().validate_date(year, day))?,
+            None => AocTime::now().validate_year(year)?,
+        }
+        let mut day_settings = file.days.clone();
+        if args.timeout.is_some() {
+            // A timeout on the command line applies to every day
+            day_settings.values_mut().for_each(|day| day.timeout = None);
+        }
+        Ok(RunConfig {
+            year,
+            days,
+            part: match args.part {
+                Some(num) => Some(Parts::new(num)?),
+                None => None,
+            },
+            test,
+            workspace_dir,
+            database: database.with_profile(&file.profile(args.profile)),
+            session: args.session.map(SessionToken::from),
+            input: match args.input {
+                Some(path) => Some(read_input(&path)?),
+                None => None,
+            },
+            benchmark: args.benchmark || file.benchmark.enabled,
+            jobs: args.jobs.or(file.benchmark.jobs).unwrap_or(1),
+            serial_benchmark: args.serial_benchmark || file.benchmark.serial,
+            format: args.format.or(file.format).unwrap_or_default(),
+            compare: args.compare,
+            baseline: args.baseline,
+            save_baseline: args.save_baseline,
+            threshold: args
+                .threshold
+                .or(file.benchmark.threshold)
+                .unwrap_or(DEFAULT_THRESHOLD),
+            timeout: file.timeout(args.timeout),
+            day_settings,
+        })
+    }
+
+    /// Time a part of the day may run, the setting of the day in `aou.toml` wins
+    pub fn timeout(&self, day: u8) -> Option<Duration> {
+        match self
+            .day_settings
+            .get(&day)
+            .and_then(|section| section.timeout)
+        {
+            Some(seconds) => Some(Duration::from_secs(seconds)),
+            None => self.timeout,
+        }
+    }
+
+    /// Checks if the day is benchmarked, which can be turned off per day in `aou.toml`
+    pub fn benchmarks(&self, day: u8) -> bool {
+        self.benchmark
+            && self
+                .day_settings
+                .get(&day)
+                .and_then(|section| section.benchmark)
+                .unwrap_or(true)
+    }
+
     pub fn loader_paths(&self) -> Result<Vec<DirEntry>, Box<dyn Error>> {
         let mut matching_files = Vec::new();
 
@@ -49,10 +249,10 @@ impl RunConfig {
         Ok(matching_files)
     }
 
-    pub fn get_run_counts(&self, first_run_duration: Duration) -> (u32, u32) {
+    pub fn get_run_counts(&self, day: u8, first_run_duration: Duration) -> (u32, u32) {
         // (warmup, measurement)
         match first_run_duration {
-            _ if !self.benchmark => (1, 1),
+            _ if !self.benchmarks(day) => (1, 1),
             d if d < Duration::from_millis(10) => (5, 10), // Very fast: More runs needed
             d if d < Duration::from_millis(100) => (3, 5), // Fast: Moderate runs
             d if d < Duration::from_secs(1) => (2, 3),     // Medium: Fewer runs
@@ -61,53 +261,403 @@ impl RunConfig {
     }
 }
 
+impl FilesConfig {
+    fn from_args(
+        args: FilesArgs,
+        database: AocDatabase,
+        file: &ConfigFile,
+    ) -> Result<Self, AocError> {
+        if let Some(year) = args.year {
+            AocTime::now().validate_year(year)?;
+        }
+        Ok(FilesConfig {
+            year: args.year,
+            pattern: args.pattern,
+            test: args.test,
+            database: database.with_profile(&file.profile(args.profile)),
+        })
+    }
+}
+
+/// Reads `aou run 5` as day 5 of the inferred year, as no Advent of Code year is that small
+fn split_day(year: Option<i32>, day: Option<u8>) -> (Option<i32>, Option<u8>) {
+    match (year, day) {
+        (Some(year), None) if year < AOC_START_YEAR => match u8::try_from(year) {
+            Ok(day) => (None, Some(day)),
+            Err(_) => (Some(year), None),
+        },
+        other => other,
+    }
+}
+
+/// The year given on the command line, else the one of `aou.toml`, the one in the name of the
+/// solution library or the latest Advent of Code
+fn infer_year(year: Option<i32>, file: &ConfigFile, library_dir: &Path, time: &AocTime) -> i32 {
+    year.or(file.year)
+        .or_else(|| loader::library_year(library_dir))
+        .unwrap_or_else(|| time.current_year())
+}
+
+/// The year and days `aou run` executes. Without any arguments this is today's puzzle during
+/// the Advent and every day of the inferred year otherwise
+fn select_days(
+    year: Option<i32>,
+    days: Option<DaySelection>,
+    file: &ConfigFile,
+    library_dir: &Path,
+    time: &AocTime,
+) -> (i32, Option<DaySelection>) {
+    let (year, days) = match days {
+        Some(days) => (year, Some(days)),
+        None => {
+            let (year, day) = split_day(year, None);
+            (year, day.map(DaySelection::single))
+        }
+    };
+    let days = match (year, days) {
+        (None, None) => {
+            // Today's puzzle during the Advent, unless a year was asked for
+            let year = infer_year(None, file, library_dir, time);
+            time.current_day(year).map(DaySelection::single)
+        }
+        (_, days) => days,
+    };
+    (infer_year(year, file, library_dir, time), days)
+}
+
+/// Secret the input encryption key is derived from: the contents of the key file or
+/// `$AOU_PASSPHRASE`. Inputs are stored unencrypted if neither is given
+fn encryption_secret(key_file: Option<&Path>) -> Result<Option<Vec<u8>>, AocError> {
+    if let Some(path) = key_file {
+        let secret = read_input(path)?;
+        return Ok(Some(secret.trim_end().as_bytes().to_vec()));
+    }
+    Ok(std::env::var_os(PASSPHRASE_VAR)
+        .filter(|passphrase| !passphrase.is_empty())
+        .map(|passphrase| passphrase.into_encoded_bytes()))
+}
+
+/// Moves the clock of [`AocTime::now`] to `$AOU_NOW` if it is set
+fn shift_clock() -> Result<(), AocError> {
+    let Some(value) = std::env::var(NOW_VAR)
+        .ok()
+        .filter(|value| !value.is_empty())
+    else {
+        return Ok(());
+    };
+    let start = DateTime::parse_from_rfc3339(&value).map_err(|error| AocError::InvalidTime {
+        value: value.clone(),
+        reason: error.to_string(),
+    })?;
+    AocTime::set_clock(ShiftedClock::starting_at(start.with_timezone(&Utc)));
+    Ok(())
+}
+
 impl Config {
     pub fn from_cli(cli: Cli) -> Result<Self, AocError> {
-        match cli {
-            Cli::Run(args) => {
-                match args.day {
-                    Some(day) => AocTime::now().validate_date(args.year, day)?,
-                    None => AocTime::now().validate_year(args.year)?,
+        shift_clock()?;
+        let file = ConfigFile::discover(&std::env::current_dir().unwrap_or_default())?;
+        let database = || {
+            let database = match &cli.data_dir {
+                Some(dir) => AocDatabase::in_dir(dir)?,
+                None => AocDatabase::new()?,
+            };
+            let storage: Option<Arc<dyn AocStorage>> = match &cli.storage_dir {
+                Some(dir) => Some(Arc::new(FileStorage::new(dir))),
+                None => None,
+            };
+            let storage = match encryption_secret(cli.key_file.as_deref())? {
+                Some(secret) => {
+                    let inner = storage.unwrap_or_else(|| Arc::new(database.clone()));
+                    Some(Arc::new(EncryptedStorage::new(inner, &secret)) as Arc<dyn AocStorage>)
                 }
-                Ok(Self::Run(RunConfig {
+                None => storage,
+            };
+            Ok::<_, AocError>(match storage {
+                Some(storage) => database.with_storage(storage),
+                None => database,
+            })
+        };
+        match cli.command {
+            Command::Run(args) => Ok(Self::Run(RunConfig::from_args(
+                args,
+                false,
+                database()?,
+                &file,
+            )?)),
+            Command::Test(args) => Ok(Self::Run(RunConfig::from_args(
+                args,
+                true,
+                database()?,
+                &file,
+            )?)),
+            Command::AddTest(args) => {
+                AocTime::now().validate_date(args.year, args.day)?;
+                Ok(Self::AddTest(AddTestConfig {
                     year: args.year,
                     day: args.day,
-                    part: match args.part {
-                        Some(num) => Some(Parts::new(num)?),
-                        None => None,
+                    database: database()?,
+                }))
+            }
+            Command::Fetch(args) => {
+                let (year, day) = split_day(args.year, args.day);
+                let year = infer_year(
+                    year,
+                    &file,
+                    &file.library_dir(args.workspace_dir.clone()),
+                    &AocTime::now(),
+                );
+                let flag = [
+                    (args.wait, "--wait"),
+                    (args.scaffold, "--scaffold"),
+                    (args.open, "--open"),
+                ]
+                .into_iter()
+                .find_map(|(set, flag)| set.then_some(flag));
+                if let (Some(flag), None) = (flag, day) {
+                    return Err(AocError::MissingDay {
+                        flag: flag.to_string(),
+                    });
+                }
+                match day {
+                    Some(day) if args.wait => AocTime::now().validate_upcoming(year, day)?,
+                    Some(day) => AocTime::now().validate_date(year, day)?,
+                    None => AocTime::now().validate_year(year)?,
+                }
+                Ok(Self::Fetch(FetchConfig {
+                    year,
+                    day,
+                    wait: args.wait,
+                    scaffold: args.scaffold,
+                    open: args.open,
+                    workspace_dir: file.workspace_dir(args.workspace_dir),
+                    database: database()?.with_profile(&file.profile(args.profile)),
+                    session: args.session.map(SessionToken::from),
+                }))
+            }
+            Command::Cache(command) => {
+                // Hashes and archives only exist for the SQLite tables
+                let unsupported = match &command {
+                    CacheCommand::Verify { .. } => Some("aou cache verify"),
+                    CacheCommand::Export { .. } => Some("aou cache export"),
+                    CacheCommand::Import { .. } => Some("aou cache import"),
+                    _ => None,
+                };
+                if let (Some(command), Some(_)) = (unsupported, &cli.storage_dir) {
+                    return Err(AocError::UnsupportedStorage {
+                        command: command.to_string(),
+                    });
+                }
+                Ok(Self::Cache(match command {
+                    CacheCommand::List { year, profile } => CacheConfig::List {
+                        year,
+                        database: database()?.with_profile(&file.profile(profile)),
+                    },
+                    CacheCommand::Clear {
+                        year,
+                        day,
+                        answers,
+                        profile,
+                    } => CacheConfig::Clear {
+                        year,
+                        day,
+                        answers,
+                        database: database()?.with_profile(&file.profile(profile)),
+                    },
+                    CacheCommand::Verify {
+                        year,
+                        rehash,
+                        profile,
+                    } => CacheConfig::Verify {
+                        year,
+                        rehash,
+                        database: database()?.with_profile(&file.profile(profile)),
+                    },
+                    CacheCommand::Normalize {
+                        year,
+                        day,
+                        trim_newline,
+                        crlf,
+                    } => {
+                        AocTime::now().validate_date(year, day)?;
+                        CacheConfig::Normalize {
+                            year,
+                            day,
+                            normalization: InputNormalization::new(trim_newline, crlf),
+                            database: database()?,
+                        }
+                    }
+                    CacheCommand::Export { file } => CacheConfig::Export {
+                        file,
+                        database: database()?,
+                    },
+                    CacheCommand::Import { file } => CacheConfig::Import {
+                        file,
+                        database: database()?,
+                    },
+                }))
+            }
+            Command::Import(args) => Ok(Self::Import(FilesConfig::from_args(
+                args,
+                database()?,
+                &file,
+            )?)),
+            Command::Export(args) => Ok(Self::Export(FilesConfig::from_args(
+                args,
+                database()?,
+                &file,
+            )?)),
+            Command::Readme(args) => {
+                let workspace_dir = file.library_dir(args.workspace_dir);
+                let year = infer_year(args.year, &file, &workspace_dir, &AocTime::now());
+                AocTime::now().validate_year(year)?;
+                Ok(Self::Readme(ReadmeConfig {
+                    run: RunConfig {
+                        year,
+                        days: None,
+                        part: None,
+                        test: false,
+                        workspace_dir,
+                        database: database()?.with_profile(&file.profile(args.profile)),
+                        session: None,
+                        input: None,
+                        benchmark: args.benchmark || file.benchmark.enabled,
+                        jobs: 1,
+                        serial_benchmark: false,
+                        format: OutputFormat::Table,
+                        compare: false,
+                        baseline: None,
+                        save_baseline: None,
+                        threshold: 0.0,
+                        timeout: file.timeout(None),
+                        day_settings: file.days.clone(),
                     },
-                    test: false,
-                    workspace_dir: (args.workspace_dir + "/target/release").into(),
-                    database: AocDatabase::new()?,
-                    benchmark: args.benchmark,
+                    file: args.file,
+                    svg: args.svg,
                 }))
             }
-            Cli::Test(args) => {
+            Command::Login(args) => Ok(Self::Login(SessionConfig {
+                token: None,
+                database: database()?.with_profile(&file.profile(args.profile)),
+            })),
+            Command::Logout(args) => Ok(Self::Logout(SessionConfig {
+                token: None,
+                database: database()?.with_profile(&file.profile(args.profile)),
+            })),
+            Command::Whoami(args) => Ok(Self::Whoami(SessionConfig {
+                token: args.session.map(SessionToken::from),
+                database: database()?.with_profile(&file.profile(args.profile)),
+            })),
+            Command::Requests(args) => Ok(Self::Requests(RequestsConfig {
+                limit: args.limit,
+                database: database()?,
+            })),
+            Command::Leaderboard(args) => {
                 match args.day {
                     Some(day) => AocTime::now().validate_date(args.year, day)?,
                     None => AocTime::now().validate_year(args.year)?,
                 }
-                Ok(Self::Run(RunConfig {
+                Ok(Self::Leaderboard(LeaderboardConfig {
                     year: args.year,
+                    id: args.id,
                     day: args.day,
-                    part: match args.part {
-                        Some(num) => Some(Parts::new(num)?),
-                        None => None,
-                    },
-                    test: true,
-                    workspace_dir: (args.workspace_dir + "/target/release").into(),
-                    database: AocDatabase::new()?,
-                    benchmark: args.benchmark,
-                }))
-            }
-            Cli::AddTest(args) => {
-                AocTime::now().validate_date(args.year, args.day)?;
-                Ok(Self::AddTest(AddTestConfig {
-                    year: args.year,
-                    day: args.day,
-                    database: AocDatabase::new()?,
+                    database: database()?.with_profile(&file.profile(args.profile)),
+                    session: args.session.map(SessionToken::from),
                 }))
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use clap::Parser;
+    use std::env::consts::DLL_EXTENSION;
+    use tempfile::TempDir;
+
+    /// Midday in Advent of Code time
+    fn at(year: i32, month: u32, day: u32) -> AocTime {
+        AocTime::at(Utc.with_ymd_and_hms(year, month, day, 17, 0, 0).unwrap())
+    }
+
+    fn select(
+        args: &[&str],
+        file: &ConfigFile,
+        library_dir: &Path,
+        time: &AocTime,
+    ) -> (i32, Option<u8>) {
+        let cli = Cli::try_parse_from(["aou", "run"].iter().chain(args)).unwrap();
+        let Command::Run(args) = cli.command else {
+            unreachable!("Parsed a run command")
+        };
+        let (year, days) = select_days(args.year, args.days, file, library_dir, time);
+        (
+            year,
+            days.map(|days| days.as_single().expect("Selected one day")),
+        )
+    }
+
+    #[test]
+    fn small_year_is_the_day() {
+        let dir = TempDir::new().unwrap();
+        let file = ConfigFile::default();
+
+        assert_eq!(
+            select(&["5"], &file, dir.path(), &at(2024, 7, 1)),
+            (2023, Some(5))
+        );
+        assert_eq!(
+            select(&["5"], &file, dir.path(), &at(2023, 12, 3)),
+            (2023, Some(5))
+        );
+        assert_eq!(
+            select(&["2023", "5"], &file, dir.path(), &at(2024, 7, 1)),
+            (2023, Some(5))
+        );
+        assert_eq!(
+            select(&["2022"], &file, dir.path(), &at(2024, 7, 1)),
+            (2022, None)
+        );
+    }
+
+    #[test]
+    fn no_arguments_run_today_in_december() {
+        let dir = TempDir::new().unwrap();
+        let file = ConfigFile::default();
+
+        assert_eq!(
+            select(&[], &file, dir.path(), &at(2023, 12, 5)),
+            (2023, Some(5))
+        );
+        // After the last puzzle and outside of December every day of the latest Advent runs
+        assert_eq!(
+            select(&[], &file, dir.path(), &at(2023, 12, 28)),
+            (2023, None)
+        );
+        assert_eq!(
+            select(&[], &file, dir.path(), &at(2024, 7, 1)),
+            (2023, None)
+        );
+    }
+
+    #[test]
+    fn year_is_taken_from_file_then_library_then_latest_event() {
+        let dir = TempDir::new().unwrap();
+        let time = at(2023, 12, 5);
+        let mut file = ConfigFile::default();
+
+        assert_eq!(infer_year(None, &file, dir.path(), &time), 2023);
+
+        let library = dir.path().join(format!("libaoc_2022.{}", DLL_EXTENSION));
+        std::fs::write(library, "").unwrap();
+        assert_eq!(infer_year(None, &file, dir.path(), &time), 2022);
+        // Today's puzzle only runs for the current Advent
+        assert_eq!(select(&[], &file, dir.path(), &time), (2022, None));
+
+        file.year = Some(2021);
+        assert_eq!(infer_year(None, &file, dir.path(), &time), 2021);
+        assert_eq!(infer_year(Some(2020), &file, dir.path(), &time), 2020);
+    }
+}
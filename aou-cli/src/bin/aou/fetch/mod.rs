@@ -0,0 +1,134 @@
+mod scaffold;
+
+use advent_of_utils_cli::{
+    error::{AocError, InputError},
+    input::get_input,
+    types::{display::Progress, AocTime},
+};
+use std::{
+    io::{stderr, Write},
+    process::Command,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::config::FetchConfig;
+
+/// Number of additional tries when the puzzle is not unlocked yet after waiting
+const UNLOCK_RETRIES: u32 = 3;
+const UNLOCK_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Random delay between 1 and 5 seconds so not everyone hits the server at midnight exactly
+fn jitter() -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    Duration::from_millis(1000 + (nanos % 4000) as u64)
+}
+
+/// Shows a countdown on stderr until the puzzle is released
+fn wait_for_release(year: i32, day: u8) {
+    while let Some(remaining) = AocTime::now().time_until_release(year, day) {
+        let seconds = remaining.num_seconds();
+        eprint!(
+            "\rDay {} unlocks in {:02}:{:02}:{:02}",
+            day,
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        );
+        let _ = stderr().flush();
+        thread::sleep(
+            (remaining - chrono::Duration::seconds(seconds))
+                .to_std()
+                .unwrap_or_default()
+                .max(Duration::from_millis(100))
+                .min(Duration::from_secs(1)),
+        );
+    }
+    eprintln!("\rDay {} is unlocked{}", day, " ".repeat(16));
+    thread::sleep(jitter());
+}
+
+/// Opens the puzzle text in the default browser
+fn open_puzzle(year: i32, day: u8) {
+    let url = format!("https://adventofcode.com/{}/day/{}", year, day);
+    let result = match std::env::consts::OS {
+        "windows" => Command::new("cmd").args(["/C", "start", &url]).status(),
+        "macos" => Command::new("open").arg(&url).status(),
+        _ => Command::new("xdg-open").arg(&url).status(),
+    };
+    if result.is_err() {
+        println!("Failed opening the browser, the puzzle is at {}", url);
+    }
+}
+
+/// Fetches every available day of the year which is not cached yet
+fn fetch_year(config: &FetchConfig) -> Result<(), AocError> {
+    let progress = Progress::new();
+    let mut fetched = 0;
+    for day in 1..=AocTime::now().available_day(config.year) {
+        if config.database.has_input(config.year, day, false)? {
+            continue;
+        }
+        get_input(
+            config.year,
+            day,
+            &config.database,
+            false,
+            config.session.as_ref(),
+            &progress,
+        )?;
+        fetched += 1;
+    }
+    println!("Fetched {} inputs of {}", fetched, config.year);
+
+    Ok(())
+}
+
+pub fn run(config: &FetchConfig) -> Result<(), AocError> {
+    let Some(day) = config.day else {
+        return fetch_year(config);
+    };
+
+    if config.wait {
+        wait_for_release(config.year, day);
+    }
+
+    let progress = Progress::new();
+    let mut retries = 0;
+    let input = loop {
+        match get_input(
+            config.year,
+            day,
+            &config.database,
+            false,
+            config.session.as_ref(),
+            &progress,
+        ) {
+            Err(AocError::Input(InputError::NotReleased { .. }))
+                if config.wait && retries < UNLOCK_RETRIES =>
+            {
+                retries += 1;
+                thread::sleep(UNLOCK_RETRY_DELAY);
+            }
+            result => break result?.0,
+        }
+    };
+    println!(
+        "Cached the input of {} day {} ({} lines)",
+        config.year,
+        day,
+        input.lines().count()
+    );
+
+    if config.scaffold {
+        scaffold::scaffold_day(&config.workspace_dir, day)?;
+    }
+    if config.open {
+        open_puzzle(config.year, day);
+    }
+
+    Ok(())
+}
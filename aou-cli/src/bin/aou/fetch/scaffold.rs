@@ -0,0 +1,136 @@
+use advent_of_utils_cli::error::{AocError, OutputError};
+use std::{fs, path::Path};
+
+/// Checks if the arguments of `add_days!` already contain the day. Like the macro only
+/// accepts `a..=b` as a range
+fn covers_day(arguments: &str, day: u8) -> bool {
+    arguments.split(',').any(|argument| {
+        let argument = argument.trim();
+        match argument.split_once("..=") {
+            Some((start, end)) => {
+                let start = start.trim().parse::<u8>().unwrap_or(u8::MAX);
+                (start..=end.trim().parse().unwrap_or(0)).contains(&day)
+            }
+            None => argument.parse::<u8>() == Ok(day),
+        }
+    })
+}
+
+fn day_template(day: u8) -> String {
+    format!(
+        "use advent_of_utils::{{AocOption, Solution}};
+
+pub struct Day{day:02};
+
+impl Solution for Day{day:02} {{
+    fn part1(&self, _input: String) -> AocOption {{
+        AocOption::None
+    }}
+
+    fn part2(&self, _input: String) -> AocOption {{
+        AocOption::None
+    }}
+}}
+"
+    )
+}
+
+/// Creates `src/dayXX.rs` and adds the day to the `add_days!` call in `src/lib.rs`
+pub(super) fn scaffold_day(workspace_dir: &Path, day: u8) -> Result<(), AocError> {
+    let day_path = workspace_dir.join("src").join(format!("day{:02}.rs", day));
+    if day_path.exists() {
+        println!("{} already exists", day_path.display());
+    } else {
+        fs::write(&day_path, day_template(day)).map_err(|source| {
+            AocError::Output(OutputError::WriteFailed {
+                path: day_path.clone(),
+                source,
+            })
+        })?;
+        println!("Created {}", day_path.display());
+    }
+
+    let lib_path = workspace_dir.join("src").join("lib.rs");
+    let lib = fs::read_to_string(&lib_path).map_err(|source| {
+        AocError::Output(OutputError::ReadFailed {
+            path: lib_path.clone(),
+            source,
+        })
+    })?;
+
+    let marker = "add_days!(";
+    let Some(start) = lib.find(marker).map(|i| i + marker.len()) else {
+        println!("No add_days! call found in {}", lib_path.display());
+        return Ok(());
+    };
+    let Some(end) = lib[start..].find(')').map(|i| i + start) else {
+        println!("No add_days! call found in {}", lib_path.display());
+        return Ok(());
+    };
+
+    let arguments = lib[start..end].trim();
+    if covers_day(arguments, day) {
+        return Ok(());
+    }
+    let arguments = match arguments {
+        "" => day.to_string(),
+        arguments => format!("{}, {}", arguments.trim_end_matches(','), day),
+    };
+
+    let lib = format!("{}{}{}", &lib[..start], arguments, &lib[end..]);
+    fs::write(&lib_path, lib).map_err(|source| {
+        AocError::Output(OutputError::WriteFailed {
+            path: lib_path.clone(),
+            source,
+        })
+    })?;
+    println!("Added day {} to {}", day, lib_path.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A workspace with the given `lib.rs` and the `src/dayXX.rs` of the day already in place
+    fn scaffold_lib(lib: &str, day: u8) -> String {
+        let dir = TempDir::new().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(src.join("lib.rs"), lib).unwrap();
+        fs::write(src.join(format!("day{:02}.rs", day)), "").unwrap();
+
+        scaffold_day(dir.path(), day).unwrap();
+        fs::read_to_string(src.join("lib.rs")).unwrap()
+    }
+
+    #[test]
+    fn ranges_cover_their_days() {
+        assert!(covers_day("1..=5", 5));
+        assert!(covers_day("1, 3..=7", 7));
+        assert!(!covers_day("3..7", 5));
+        assert!(!covers_day("1..=5, 7", 6));
+        assert!(!covers_day("", 1));
+    }
+
+    #[test]
+    fn appends_to_empty_call() {
+        let lib = "use advent_of_utils::add_days;\n\nadd_days!();\n";
+
+        assert_eq!(
+            scaffold_lib(lib, 5),
+            "use advent_of_utils::add_days;\n\nadd_days!(5);\n"
+        );
+        assert_eq!(scaffold_lib("add_days!(1, 2,);", 3), "add_days!(1, 2, 3);");
+    }
+
+    #[test]
+    fn leaves_covered_day_alone() {
+        let lib = "add_days!(1..=4, 6);\n";
+
+        assert_eq!(scaffold_lib(lib, 3), lib);
+        assert_eq!(scaffold_lib(lib, 6), lib);
+    }
+}
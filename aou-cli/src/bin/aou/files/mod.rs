@@ -0,0 +1,119 @@
+mod pattern;
+
+use advent_of_utils_cli::{
+    error::{AocError, OutputError},
+    types::{AocTime, AOC_START_YEAR},
+};
+use pattern::InputPattern;
+use std::fs;
+
+use crate::config::FilesConfig;
+
+/// Loads all input files matching the pattern into the cache
+pub fn import(config: &FilesConfig) -> Result<(), AocError> {
+    let pattern = InputPattern::new(&config.pattern, config.year)?;
+    let time = AocTime::now();
+    let years = match config.year {
+        Some(year) => year..=year,
+        None => AOC_START_YEAR..=time.current_year(),
+    };
+
+    let mut imported = 0;
+    for year in years {
+        for day in 1..=time.available_day(year) {
+            let path = pattern.path(year, day);
+            if !path.is_file() {
+                continue;
+            }
+            let input = fs::read_to_string(&path).map_err(|source| {
+                AocError::Output(OutputError::ReadFailed {
+                    path: path.clone(),
+                    source,
+                })
+            })?;
+            config.database.set_input(year, day, config.test, input)?;
+            imported += 1;
+        }
+    }
+    println!("Imported {} inputs", imported);
+
+    Ok(())
+}
+
+/// Writes all cached inputs to the files given by the pattern
+pub fn export(config: &FilesConfig) -> Result<(), AocError> {
+    let pattern = InputPattern::new(&config.pattern, config.year)?;
+
+    let mut exported = 0;
+    for entry in config.database.list_cache(config.year)? {
+        let cached = match config.test {
+            true => entry.test_input,
+            false => entry.input,
+        };
+        if !cached {
+            continue;
+        }
+
+        let path = pattern.path(entry.year, entry.day);
+        let input = config
+            .database
+            .get_input(entry.year, entry.day, config.test)?;
+        let write_failed = |source| {
+            AocError::Output(OutputError::WriteFailed {
+                path: path.clone(),
+                source,
+            })
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(write_failed)?;
+        }
+        fs::write(&path, input).map_err(write_failed)?;
+        exported += 1;
+    }
+    println!("Exported {} inputs", exported);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_utils_cli::types::AocDatabase;
+    use tempfile::TempDir;
+
+    fn files_config(database: &AocDatabase, pattern: &str) -> FilesConfig {
+        FilesConfig {
+            year: Some(2023),
+            pattern: pattern.to_string(),
+            test: false,
+            database: database.clone(),
+        }
+    }
+
+    #[test]
+    fn export_and_import_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let inputs = dir.path().join("inputs");
+        fs::create_dir(&inputs).unwrap();
+        fs::write(inputs.join("day05.txt"), "five").unwrap();
+        // Not zero-padded, so it doesn't match the pattern
+        fs::write(inputs.join("day6.txt"), "six").unwrap();
+        let pattern = format!("{}/day{{day:02}}.txt", inputs.display());
+        let database = AocDatabase::in_memory().unwrap();
+
+        import(&files_config(&database, &pattern)).unwrap();
+
+        assert_eq!(database.get_input(2023, 5, false).unwrap(), "five");
+        assert!(!database.has_input(2023, 6, false).unwrap());
+
+        let exported = dir.path().join("exported");
+        let pattern = format!("{}/{{year}}/{{day}}.txt", exported.display());
+        export(&files_config(&database, &pattern)).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(exported.join("2023").join("5.txt")).unwrap(),
+            "five"
+        );
+        assert_eq!(fs::read_dir(exported.join("2023")).unwrap().count(), 1);
+    }
+}
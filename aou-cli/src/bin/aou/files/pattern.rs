@@ -0,0 +1,93 @@
+use advent_of_utils_cli::error::AocError;
+use std::path::PathBuf;
+
+/// Path of an input file with `{year}`, `{day}` and `{day:02}` placeholders,
+/// e.g. `inputs/{year}/day{day:02}.txt`
+pub(super) struct InputPattern {
+    pattern: String,
+}
+
+impl InputPattern {
+    pub(super) fn new(pattern: &str, year: Option<i32>) -> Result<Self, AocError> {
+        let invalid = |reason: &str| AocError::InvalidPattern {
+            pattern: pattern.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let mut rest = pattern;
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| invalid("Unclosed placeholder"))?
+                + start;
+            match &rest[start..=end] {
+                "{year}" | "{day}" | "{day:02}" => {}
+                placeholder => {
+                    return Err(invalid(&format!(
+                        "Unknown placeholder {}, use {{year}}, {{day}} or {{day:02}}",
+                        placeholder
+                    )))
+                }
+            }
+            rest = &rest[end + 1..];
+        }
+
+        if !pattern.contains("{day}") && !pattern.contains("{day:02}") {
+            return Err(invalid("The pattern needs a {day} or {day:02} placeholder"));
+        }
+        if !pattern.contains("{year}") && year.is_none() {
+            return Err(invalid(
+                "The pattern needs a {year} placeholder if no year is given",
+            ));
+        }
+
+        Ok(Self {
+            pattern: pattern.to_string(),
+        })
+    }
+
+    pub(super) fn path(&self, year: i32, day: u8) -> PathBuf {
+        self.pattern
+            .replace("{year}", &year.to_string())
+            .replace("{day:02}", &format!("{:02}", day))
+            .replace("{day}", &day.to_string())
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_reason(pattern: &str, year: Option<i32>) -> String {
+        match InputPattern::new(pattern, year) {
+            Err(AocError::InvalidPattern { reason, .. }) => reason,
+            _ => panic!("{pattern} should be invalid"),
+        }
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        let pattern = InputPattern::new("inputs/{year}/day{day:02}.txt", None).unwrap();
+        assert_eq!(
+            pattern.path(2023, 5),
+            PathBuf::from("inputs/2023/day05.txt")
+        );
+        assert_eq!(
+            pattern.path(2023, 15),
+            PathBuf::from("inputs/2023/day15.txt")
+        );
+
+        let pattern = InputPattern::new("{day}/{day:02}.txt", Some(2023)).unwrap();
+        assert_eq!(pattern.path(2023, 5), PathBuf::from("5/05.txt"));
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert!(error_reason("inputs/{month}/{day}.txt", None).contains("{month}"));
+        assert!(error_reason("inputs/{year}/{day", None).contains("Unclosed"));
+        assert!(error_reason("inputs/{year}.txt", None).contains("{day}"));
+        assert!(error_reason("inputs/{day}.txt", None).contains("{year}"));
+        assert!(InputPattern::new("inputs/{day}.txt", Some(2023)).is_ok());
+    }
+}
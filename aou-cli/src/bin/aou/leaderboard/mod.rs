@@ -0,0 +1,24 @@
+use advent_of_utils_cli::{
+    error::AocError,
+    input::get_leaderboard,
+    types::display::{Progress, Table},
+};
+
+use crate::config::LeaderboardConfig;
+
+pub fn run(config: &LeaderboardConfig) -> Result<(), AocError> {
+    let leaderboard = get_leaderboard(
+        config.year,
+        config.id,
+        &config.database,
+        config.session.as_ref(),
+        &Progress::new(),
+    )?;
+    match config.day {
+        _ if leaderboard.members().is_empty() => println!("The leaderboard has no members"),
+        Some(day) => leaderboard.day_table(day),
+        None => leaderboard.table(),
+    }
+
+    Ok(())
+}
@@ -2,7 +2,7 @@ use advent_of_utils::Solution;
 use advent_of_utils_cli::error::{AocError, LoadingError};
 use libloading::{Library, Symbol};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::config::RunConfig;
@@ -15,6 +15,7 @@ struct RawSolutions {
 pub(super) struct SolutionLibrary {
     lib: Arc<Library>,
     year: i32,
+    path: PathBuf,
 }
 
 impl SolutionLibrary {
@@ -39,10 +40,15 @@ impl SolutionLibrary {
             Ok(Self {
                 lib: Arc::new(lib),
                 year: config.year,
+                path,
             })
         }
     }
 
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     pub fn get_solutions(&self) -> Result<HashMap<u8, Box<dyn Solution>>, AocError> {
         unsafe {
             let create_solutions = self
@@ -1,39 +1,100 @@
 mod ffi;
 
-use advent_of_utils::Solution;
-use advent_of_utils_cli::error::AocError;
-use std::collections::HashMap;
+use advent_of_utils::{AocOption, Solution};
+use advent_of_utils_cli::{
+    error::AocError,
+    types::{AocTime, AOC_START_YEAR},
+};
+use std::collections::{BTreeSet, HashMap};
+use std::env::consts::DLL_EXTENSION;
+use std::fs::read_dir;
+use std::path::Path;
+use std::sync::Arc;
 
 use crate::config::RunConfig;
 use ffi::SolutionLibrary;
 
+/// A solution of the library, which keeps the library loaded as long as it is used. A part
+/// that ran into its timeout may still be running after the solutions were dropped
+struct LibrarySolution {
+    solution: Box<dyn Solution>,
+    _library: Arc<SolutionLibrary>,
+}
+
+impl Solution for LibrarySolution {
+    fn part1(&self, input: String) -> AocOption {
+        self.solution.part1(input)
+    }
+
+    fn part2(&self, input: String) -> AocOption {
+        self.solution.part2(input)
+    }
+}
+
 /// Represents a collection of loaded solutions for a specific year
 pub struct Solutions {
-    solutions: HashMap<u8, Box<dyn Solution>>,
-    _library: SolutionLibrary, // Keeps the library loaded
+    solutions: HashMap<u8, Arc<dyn Solution>>,
+    library: Arc<SolutionLibrary>, // Keeps the library loaded
 }
 
 impl Solutions {
     /// Get a solution for a specific day
-    pub fn get(&self, day: u8) -> Option<&dyn Solution> {
-        self.solutions.get(&day).map(|s| s.as_ref())
+    pub fn get(&self, day: u8) -> Option<Arc<dyn Solution>> {
+        self.solutions.get(&day).cloned()
+    }
+
+    /// Get the path of the loaded solution library
+    pub fn library_path(&self) -> &Path {
+        self.library.path()
     }
 
     /// Get all solutions
-    pub fn iter(&self) -> impl Iterator<Item = (u8, &dyn Solution)> {
+    pub fn iter(&self) -> impl Iterator<Item = (u8, Arc<dyn Solution>)> + '_ {
         self.solutions
             .iter()
-            .map(|(&day, solution)| (day, solution.as_ref()))
+            .map(|(&day, solution)| (day, Arc::clone(solution)))
+    }
+}
+
+/// Year in the name of the solution libraries in the directory, e.g. 2023 for
+/// `libaoc2023.so`. `None` if there is no library or they are for different years
+pub fn library_year(dir: &Path) -> Option<i32> {
+    let latest = AocTime::now().current_year();
+    let years: BTreeSet<i32> = read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == DLL_EXTENSION))
+        .filter_map(|path| path.file_stem()?.to_str().map(str::to_string))
+        .flat_map(|name| {
+            name.split(|c: char| !c.is_ascii_digit())
+                .filter(|digits| digits.len() == 4)
+                .filter_map(|digits| digits.parse().ok())
+                .collect::<Vec<i32>>()
+        })
+        .filter(|year| (AOC_START_YEAR..=latest).contains(year))
+        .collect();
+
+    match years.len() {
+        1 => years.first().copied(),
+        _ => None,
     }
 }
 
 /// Load solutions for a specific year
 pub fn load_solutions(config: &RunConfig) -> Result<Solutions, AocError> {
-    let library = SolutionLibrary::load(config)?;
-    let solutions = library.get_solutions()?;
+    let library = Arc::new(SolutionLibrary::load(config)?);
+    let solutions = library
+        .get_solutions()?
+        .into_iter()
+        .map(|(day, solution)| {
+            let solution: Arc<dyn Solution> = Arc::new(LibrarySolution {
+                solution,
+                _library: Arc::clone(&library),
+            });
+            (day, solution)
+        })
+        .collect();
 
-    Ok(Solutions {
-        solutions,
-        _library: library,
-    })
+    Ok(Solutions { solutions, library })
 }
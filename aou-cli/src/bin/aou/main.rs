@@ -1,40 +1,139 @@
 mod adder;
+mod cache;
 mod config;
+mod fetch;
+mod files;
+mod leaderboard;
 mod loader;
+mod readme;
+mod requests;
 mod runner;
+mod session;
 
-use clap::{Args, Parser};
-use config::Config;
-use std::process;
+use advent_of_utils_cli::types::display::OutputFormat;
+use clap::{Args, Parser, Subcommand};
+use config::{Config, DaySelection};
+use std::{path::PathBuf, process};
 
 #[derive(Parser)]
+#[command(after_help = "Environment:\n  \
+    AOU_NOW  Act as if it was this RFC 3339 time, e.g. 2024-11-30T23:59:30-05:00, to try \
+    puzzle releases. The clock keeps running from there")]
 /// CLI for the Advent of Utils library
-enum Cli {
+struct Cli {
+    /// Directory of the database instead of the local data directory
+    #[arg(long, global = true, env = "AOU_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+
+    /// Keep inputs and answers as plain files in this directory instead of the database
+    #[arg(long, global = true)]
+    storage_dir: Option<PathBuf>,
+
+    /// Encrypt cached inputs with the contents of this file instead of $AOU_PASSPHRASE
+    #[arg(long, global = true)]
+    key_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
     /// Run a certain day or a whole year against your official input
     Run(RunArgs),
     /// Test your implementation against the by you defined test cases
     Test(RunArgs),
     /// Add a test case to a day
     AddTest(AddArgs),
+    /// Download and cache the input of a day or all available days of a year
+    Fetch(FetchArgs),
+    /// Inspect, clear, export and import the cached inputs and answers
+    #[command(subcommand)]
+    Cache(CacheCommand),
+    /// Load input files matching a path pattern into the cache
+    Import(FilesArgs),
+    /// Write the cached inputs to files given by a path pattern
+    Export(FilesArgs),
+    /// Render the stars and timings of a year into a marked section of a file
+    Readme(ReadmeArgs),
+    /// Store your Advent of Code session token, read from the terminal without echo or stdin
+    Login(ProfileArgs),
+    /// Remove the stored session token
+    Logout(ProfileArgs),
+    /// Validate the session token and show the account it belongs to
+    Whoami(SessionArgs),
+    /// Show the latest requests made to Advent of Code
+    Requests(RequestsArgs),
+    /// Show the members, stars and completion times of a private leaderboard
+    Leaderboard(LeaderboardArgs),
 }
 
 #[derive(Args)]
 #[command(author, version, about, long_about = None)]
 struct RunArgs {
+    /// Year of the puzzles. Taken from aou.toml, the name of the solution library or the
+    /// latest Advent if not given. A number below 2015 is read as the day
     #[arg()]
-    year: i32,
+    year: Option<i32>,
 
+    /// Days to run as a list of days and ranges like `1-5,9,12-`. Runs all days if not given,
+    /// or today's puzzle in December if no year is given either
     #[arg()]
-    day: Option<u8>,
+    days: Option<DaySelection>,
 
     #[arg(short, long)]
     part: Option<u8>,
 
-    #[arg(long, default_value = ".")]
-    workspace_dir: String,
+    /// Root of the solution crate. Taken from aou.toml or the current directory if not given
+    #[arg(long)]
+    workspace_dir: Option<PathBuf>,
+
+    /// Run against this file, or stdin if `-`, instead of the cached input. Nothing is stored
+    #[arg(short, long, conflicts_with_all = ["compare", "save_baseline"])]
+    input: Option<PathBuf>,
+
+    /// Session token to use instead of $AOC_SESSION or the stored one
+    #[arg(long)]
+    session: Option<String>,
+
+    /// Profile whose session token, inputs and answers are used
+    #[arg(long)]
+    profile: Option<String>,
 
     #[arg(short, long)]
     benchmark: bool,
+
+    /// Number of days to run in parallel
+    #[arg(short, long, value_parser = clap::value_parser!(u16).range(1..))]
+    jobs: Option<u16>,
+
+    /// Never benchmark two parts at the same time when running in parallel
+    #[arg(long)]
+    serial_benchmark: bool,
+
+    /// Format the results are printed in
+    #[arg(short, long, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Compare the benchmark against the previous run and fail on regressions
+    #[arg(long, requires = "benchmark")]
+    compare: bool,
+
+    /// Compare against the named baseline instead of the previous run
+    #[arg(long, requires = "compare")]
+    baseline: Option<String>,
+
+    /// Store this benchmark run as a named baseline
+    #[arg(long, requires = "benchmark")]
+    save_baseline: Option<String>,
+
+    /// Slowdown in percent above which a part counts as a regression [default: 10]
+    #[arg(long)]
+    threshold: Option<f64>,
+
+    /// Seconds a part may run before aou stops
+    #[arg(long)]
+    timeout: Option<u64>,
 }
 
 #[derive(Args)]
@@ -47,6 +146,189 @@ struct AddArgs {
     day: u8,
 }
 
+#[derive(Args)]
+#[command(author, version, about, long_about = None)]
+struct FetchArgs {
+    /// Year of the puzzles. Taken from aou.toml, the name of the solution library or the
+    /// latest Advent if not given. A number below 2015 is read as the day
+    #[arg()]
+    year: Option<i32>,
+
+    /// Day to fetch. Fetches all available days of the year if not given
+    #[arg()]
+    day: Option<u8>,
+
+    /// Wait until the puzzle unlocks at midnight EST before downloading
+    #[arg(short, long)]
+    wait: bool,
+
+    /// Create `src/dayXX.rs` and add the day to `add_days!` in `src/lib.rs`
+    #[arg(long)]
+    scaffold: bool,
+
+    /// Open the puzzle text in the browser
+    #[arg(long)]
+    open: bool,
+
+    /// Root of the solution crate. Taken from aou.toml or the current directory if not given
+    #[arg(long)]
+    workspace_dir: Option<PathBuf>,
+
+    /// Session token to use instead of $AOC_SESSION or the stored one
+    #[arg(long)]
+    session: Option<String>,
+
+    /// Profile whose session token, inputs and answers are used
+    #[arg(long)]
+    profile: Option<String>,
+}
+
+#[derive(Args)]
+#[command(author, version, about, long_about = None)]
+struct FilesArgs {
+    /// Only use the inputs of this year
+    #[arg()]
+    year: Option<i32>,
+
+    /// Path of the input files with `{year}`, `{day}` and `{day:02}` placeholders,
+    /// e.g. `inputs/{year}/day{day:02}.txt`
+    #[arg(long)]
+    pattern: String,
+
+    /// Use the test inputs instead of the puzzle inputs
+    #[arg(long)]
+    test: bool,
+
+    /// Profile whose session token, inputs and answers are used
+    #[arg(long)]
+    profile: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// Show which inputs, test inputs and answers are cached
+    List {
+        #[arg()]
+        year: Option<i32>,
+
+        /// Profile whose session token, inputs and answers are used
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Remove cached inputs so they get fetched again. Test cases are kept
+    Clear {
+        #[arg()]
+        year: Option<i32>,
+
+        #[arg(requires = "year")]
+        day: Option<u8>,
+
+        /// Also remove the stored answers
+        #[arg(long)]
+        answers: bool,
+
+        /// Profile whose session token, inputs and answers are used
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Check the cached inputs against their stored SHA-256 hashes
+    Verify {
+        #[arg()]
+        year: Option<i32>,
+
+        /// Store the hash of unhashed inputs and of inputs you changed on purpose
+        #[arg(long)]
+        rehash: bool,
+
+        /// Profile whose session token, inputs and answers are used
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Set how the input of a day is normalized before it is handed to the solution.
+    /// Without flags the input is passed unchanged
+    Normalize {
+        #[arg()]
+        year: i32,
+
+        #[arg()]
+        day: u8,
+
+        /// Remove trailing newlines
+        #[arg(long)]
+        trim_newline: bool,
+
+        /// Replace CRLF line endings with LF
+        #[arg(long)]
+        crlf: bool,
+    },
+    /// Write the inputs and answers of all profiles to a JSON archive
+    Export {
+        #[arg()]
+        file: PathBuf,
+    },
+    /// Merge a JSON archive written by `aou cache export` into the cache
+    Import {
+        #[arg()]
+        file: PathBuf,
+    },
+}
+
+#[derive(Args)]
+#[command(author, version, about, long_about = None)]
+struct ReadmeArgs {
+    /// Year of the puzzles. Taken from aou.toml, the name of the solution library or the
+    /// latest Advent if not given
+    #[arg()]
+    year: Option<i32>,
+
+    /// File containing the `<!-- aou:<YEAR>:start -->` and `<!-- aou:<YEAR>:end -->` markers
+    #[arg(long, default_value = "README.md")]
+    file: PathBuf,
+
+    /// Also write an SVG calendar to this path and embed it in the section
+    #[arg(long)]
+    svg: Option<PathBuf>,
+
+    /// Root of the solution crate. Taken from aou.toml or the current directory if not given
+    #[arg(long)]
+    workspace_dir: Option<PathBuf>,
+
+    /// Profile whose session token, inputs and answers are used
+    #[arg(long)]
+    profile: Option<String>,
+
+    #[arg(short, long)]
+    benchmark: bool,
+}
+
+#[derive(Args)]
+#[command(author, version, about, long_about = None)]
+struct ProfileArgs {
+    /// Profile whose session token, inputs and answers are used
+    #[arg(long)]
+    profile: Option<String>,
+}
+
+#[derive(Args)]
+#[command(author, version, about, long_about = None)]
+struct SessionArgs {
+    /// Session token to use instead of $AOC_SESSION or the stored one
+    #[arg(long)]
+    session: Option<String>,
+
+    /// Profile whose session token, inputs and answers are used
+    #[arg(long)]
+    profile: Option<String>,
+}
+
+#[derive(Args)]
+#[command(author, version, about, long_about = None)]
+struct RequestsArgs {
+    /// Number of requests to show
+    #[arg(short, long, default_value_t = 20)]
+    limit: usize,
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -61,8 +343,41 @@ fn main() {
     if let Err(error) = match config {
         Config::Run(config) => runner::run(&config),
         Config::AddTest(config) => adder::run(&config),
+        Config::Fetch(config) => fetch::run(&config),
+        Config::Cache(config) => cache::run(&config),
+        Config::Import(config) => files::import(&config),
+        Config::Export(config) => files::export(&config),
+        Config::Readme(config) => readme::run(&config),
+        Config::Login(config) => session::login(&config),
+        Config::Logout(config) => session::logout(&config),
+        Config::Whoami(config) => session::whoami(&config),
+        Config::Requests(config) => requests::run(&config),
+        Config::Leaderboard(config) => leaderboard::run(&config),
     } {
         println!("{error}");
         process::exit(1);
     };
 }
+
+#[derive(Args)]
+#[command(author, version, about, long_about = None)]
+struct LeaderboardArgs {
+    #[arg()]
+    year: i32,
+
+    /// ID of the leaderboard, the number at the end of its URL
+    #[arg()]
+    id: u64,
+
+    /// Show when the members solved the parts of this day instead of the stars of all days
+    #[arg(short, long)]
+    day: Option<u8>,
+
+    /// Session token to use instead of $AOC_SESSION or the stored one
+    #[arg(long)]
+    session: Option<String>,
+
+    /// Profile whose session token, inputs and answers are used
+    #[arg(long)]
+    profile: Option<String>,
+}
@@ -0,0 +1,196 @@
+use advent_of_utils_cli::{
+    error::{AocError, OutputError},
+    types::{AocCalendar, AocTime},
+};
+use std::{
+    fs,
+    path::{Component, Path},
+};
+
+use crate::{config::ReadmeConfig, loader, runner::executor};
+
+/// Replaces everything between the start and end marker of the year with the section
+fn replace_section(
+    path: &Path,
+    content: &str,
+    year: i32,
+    section: &str,
+) -> Result<String, AocError> {
+    let start_marker = format!("<!-- aou:{}:start -->", year);
+    let end_marker = format!("<!-- aou:{}:end -->", year);
+
+    let missing = |marker: &str| {
+        AocError::Output(OutputError::MissingMarker {
+            path: path.to_path_buf(),
+            marker: marker.to_string(),
+        })
+    };
+    let start = content
+        .find(&start_marker)
+        .ok_or_else(|| missing(&start_marker))?
+        + start_marker.len();
+    let end = content[start..]
+        .find(&end_marker)
+        .ok_or_else(|| missing(&end_marker))?
+        + start;
+
+    Ok(format!(
+        "{}\n{}\n{}",
+        &content[..start],
+        section.trim_end(),
+        &content[end..]
+    ))
+}
+
+/// Path of `target` as seen from the directory `from`, joined with `/` for a markdown link.
+/// Both paths have to be absolute
+fn relative_link(from: &Path, target: &Path) -> String {
+    let from: Vec<Component> = from.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = from
+        .iter()
+        .zip(target.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    std::iter::repeat_n("..".to_string(), from.len() - common)
+        .chain(
+            target[common..]
+                .iter()
+                .map(|component| component.as_os_str().to_string_lossy().to_string()),
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Links the image relative to the directory of the file it is shown in
+fn svg_link(file: &Path, svg: &Path) -> String {
+    let dir = file
+        .canonicalize()
+        .ok()
+        .and_then(|file| file.parent().map(Path::to_path_buf));
+    match (dir, svg.canonicalize()) {
+        (Some(dir), Ok(svg)) => relative_link(&dir, &svg),
+        _ => svg.to_string_lossy().to_string(),
+    }
+}
+
+pub fn run(config: &ReadmeConfig) -> Result<(), AocError> {
+    let run = &config.run;
+    let solutions = loader::load_solutions(run)?;
+    let results = executor::run_solutions(run, &solutions)?;
+
+    let days = AocTime::now().available_day(run.year);
+    let stars = run.database.stars(run.year)?;
+    let calendar = AocCalendar::new(run.year, days, stars, results);
+
+    if let Some(svg) = &config.svg {
+        fs::write(svg, calendar.svg()).map_err(|source| {
+            AocError::Output(OutputError::WriteFailed {
+                path: svg.clone(),
+                source,
+            })
+        })?;
+    }
+
+    let content = fs::read_to_string(&config.file).map_err(|source| {
+        AocError::Output(OutputError::ReadFailed {
+            path: config.file.clone(),
+            source,
+        })
+    })?;
+    let link = config.svg.as_ref().map(|svg| svg_link(&config.file, svg));
+    let section = calendar.markdown(link.as_deref());
+    let content = replace_section(&config.file, &content, run.year, &section)?;
+    fs::write(&config.file, content).map_err(|source| {
+        AocError::Output(OutputError::WriteFailed {
+            path: config.file.clone(),
+            source,
+        })
+    })?;
+
+    println!(
+        "Updated {} with {} stars for {}",
+        config.file.display(),
+        calendar.star_count(),
+        run.year
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const README: &str =
+        "# Solutions\n\n<!-- aou:2023:start -->\nold\n<!-- aou:2023:end -->\n\nMore text\n";
+
+    fn missing_marker(content: &str) -> String {
+        match replace_section(Path::new("README.md"), content, 2023, "new") {
+            Err(AocError::Output(OutputError::MissingMarker { marker, .. })) => marker,
+            _ => panic!("The section should not be found"),
+        }
+    }
+
+    #[test]
+    fn replaces_only_the_section() {
+        let replaced = replace_section(Path::new("README.md"), README, 2023, "new\n").unwrap();
+
+        assert_eq!(
+            replaced,
+            "# Solutions\n\n<!-- aou:2023:start -->\nnew\n<!-- aou:2023:end -->\n\nMore text\n"
+        );
+        assert_eq!(
+            replace_section(Path::new("README.md"), &replaced, 2023, "new").unwrap(),
+            replaced
+        );
+    }
+
+    #[test]
+    fn needs_both_markers_in_order() {
+        assert_eq!(
+            missing_marker("<!-- aou:2023:end -->"),
+            "<!-- aou:2023:start -->"
+        );
+        assert_eq!(
+            missing_marker("<!-- aou:2023:start -->"),
+            "<!-- aou:2023:end -->"
+        );
+        assert_eq!(
+            missing_marker("<!-- aou:2023:end -->\n<!-- aou:2023:start -->"),
+            "<!-- aou:2023:end -->"
+        );
+        assert_eq!(
+            missing_marker("<!-- aou:2022:start -->\n<!-- aou:2022:end -->"),
+            "<!-- aou:2023:start -->"
+        );
+    }
+
+    #[test]
+    fn links_svg_from_the_readme() {
+        assert_eq!(
+            relative_link(Path::new("/repo/docs"), Path::new("/repo/docs/cal.svg")),
+            "cal.svg"
+        );
+        assert_eq!(
+            relative_link(Path::new("/repo/docs"), Path::new("/repo/img/cal.svg")),
+            "../img/cal.svg"
+        );
+        assert_eq!(
+            relative_link(Path::new("/repo"), Path::new("/repo/docs/cal.svg")),
+            "docs/cal.svg"
+        );
+
+        let dir = TempDir::new().unwrap();
+        let docs = dir.path().join("docs");
+        fs::create_dir(&docs).unwrap();
+        fs::write(docs.join("README.md"), README).unwrap();
+        fs::write(docs.join("cal.svg"), "").unwrap();
+        assert_eq!(
+            svg_link(&docs.join("README.md"), &docs.join("cal.svg")),
+            "cal.svg"
+        );
+    }
+}
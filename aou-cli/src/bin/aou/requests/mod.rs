@@ -0,0 +1,17 @@
+use advent_of_utils_cli::{
+    error::AocError,
+    types::{display::Table, AocRequestLog},
+};
+
+use crate::config::RequestsConfig;
+
+pub fn run(config: &RequestsConfig) -> Result<(), AocError> {
+    let requests = config.database.get_requests(config.limit)?;
+    if requests.is_empty() {
+        println!("No requests were made yet");
+    } else {
+        AocRequestLog::new(requests).table();
+    }
+
+    Ok(())
+}
@@ -1,122 +1,411 @@
+use advent_of_utils::{AocOption, Solution};
 use advent_of_utils_cli::{
     error::{AocError, SolutionError},
     input::get_input,
+    types::display::Progress,
     types::AocTime,
-    types::{AocResult, AocYear},
+    types::{AocEvent, AocResult, AocYear, Parts},
+};
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
 };
-use std::collections::HashSet;
 
 use crate::{config::RunConfig, loader};
 
+/// Interval in which the live progress display is redrawn
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Held while a part is executed if benchmarks should not run concurrently
+static BENCHMARK_LOCK: Mutex<()> = Mutex::new(());
+
+/// The solution of a day
+type DayTask = (u8, Arc<dyn Solution>);
+
 pub(crate) fn run_solutions(
     config: &RunConfig,
     solutions: &loader::Solutions,
 ) -> Result<AocYear, AocError> {
-    let mut tasks: HashSet<AocResult> = HashSet::new();
     let year = config.year;
 
-    match config.day {
-        Some(day) => {
-            let solver = solutions
-                .get(day)
-                .ok_or(AocError::Solution(SolutionError::NotImplemented))?;
-
-            schedule_day_tasks(&mut tasks, solver, day, config)?;
-        }
-        None => {
-            let time = AocTime::now();
-            for (day, solver) in solutions
-                .iter()
-                .filter(|(day, _)| time.is_puzzle_available(year, *day))
-            {
-                schedule_day_tasks(&mut tasks, solver, day, config)?;
+    let time = AocTime::now();
+    let days: Vec<DayTask> = match &config.days {
+        Some(selection) => {
+            let mut days = Vec::new();
+            let mut missing = Vec::new();
+            for day in selection.days(time.available_day(year)) {
+                match solutions.get(day) {
+                    Some(solver) => days.push((day, solver)),
+                    None => missing.push(day),
+                }
+            }
+            if !missing.is_empty() {
+                let error = SolutionError::NotImplemented {
+                    year,
+                    days: missing,
+                };
+                if days.is_empty() {
+                    return Err(AocError::Solution(error));
+                }
+                eprintln!("{error}, skipping");
             }
+            days
         }
-    }
+        None => solutions
+            .iter()
+            .filter(|(day, _)| time.is_puzzle_available(year, *day))
+            .collect(),
+    };
+
+    run_days(config, days)
+}
+
+/// Runs the parts of the days while showing the progress
+fn run_days(config: &RunConfig, days: Vec<DayTask>) -> Result<AocYear, AocError> {
+    let year = config.year;
+    let progress = Progress::new();
+    let done = AtomicBool::new(false);
+
+    let tasks = thread::scope(|scope| {
+        if progress.is_live() {
+            scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    progress.render();
+                    thread::sleep(PROGRESS_INTERVAL);
+                }
+            });
+        }
+
+        let tasks = if config.jobs > 1 && days.len() > 1 {
+            run_parallel(days, config, &progress)
+        } else {
+            let mut tasks: HashSet<AocResult> = HashSet::new();
+            days.iter()
+                .try_for_each(|(day, solver)| {
+                    schedule_day_tasks(&mut tasks, solver, *day, config, &progress)
+                })
+                .map(|_| tasks)
+        };
+
+        done.store(true, Ordering::Relaxed);
+        tasks
+    });
+    progress.clear();
+
+    let free_star = match AocEvent::of(year).free_star() {
+        Some(star) => config.database.stars(year)?.contains(&star),
+        None => false,
+    };
+    Ok(collect_results(year, tasks?).with_free_star(free_star))
+}
 
-    collect_results(tasks)
+/// Runs the days on `config.jobs` worker threads. After a failed day no further days are
+/// started
+fn run_parallel(
+    days: Vec<DayTask>,
+    config: &RunConfig,
+    progress: &Progress,
+) -> Result<HashSet<AocResult>, AocError> {
+    let queue = Mutex::new(days.into_iter());
+    let tasks: Mutex<HashSet<AocResult>> = Mutex::new(HashSet::new());
+    let failed = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..config.jobs)
+            .map(|_| {
+                scope.spawn(|| -> Result<(), AocError> {
+                    while !failed.load(Ordering::Relaxed) {
+                        let next = queue.lock().expect("Task queue poisoned").next();
+                        let Some((day, solver)) = next else {
+                            return Ok(());
+                        };
+                        let mut day_tasks = HashSet::new();
+                        if let Err(error) =
+                            schedule_day_tasks(&mut day_tasks, &solver, day, config, progress)
+                        {
+                            failed.store(true, Ordering::Relaxed);
+                            return Err(error);
+                        }
+                        tasks.lock().expect("Results poisoned").extend(day_tasks);
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("Worker thread panicked"))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect::<Result<(), AocError>>()
+    })?;
+
+    Ok(tasks.into_inner().expect("Results poisoned"))
 }
 
 fn schedule_day_tasks(
     tasks: &mut HashSet<AocResult>,
-    solver: &dyn advent_of_utils::Solution,
+    solver: &Arc<dyn Solution>,
     day: u8,
     config: &RunConfig,
+    progress: &Progress,
 ) -> Result<(), AocError> {
-    let part = match config.part {
-        Some(part) => part as u8,
-        None => 0,
-    };
-    if part != 2 {
-        schedule_part_task(tasks, 1, day, solver, config)?;
-    }
-    if part != 1 {
-        schedule_part_task(tasks, 2, day, solver, config)?;
-    }
-    Ok(())
+    let event = AocEvent::of(config.year);
+    [Parts::Part1, Parts::Part2]
+        .into_iter()
+        .filter(|&part| config.part.is_none_or(|selected| selected == part))
+        // The free star is not solved, there is nothing to run
+        .filter(|&part| event.has_puzzle(day, part))
+        .try_for_each(|part| schedule_part_task(tasks, part, day, solver, config, progress))
 }
 
 fn schedule_part_task(
     tasks: &mut HashSet<AocResult>,
-    part: u8,
+    part: Parts,
     day: u8,
-    solver: &dyn advent_of_utils::Solution,
+    solver: &Arc<dyn Solution>,
     config: &RunConfig,
+    progress: &Progress,
 ) -> Result<(), AocError> {
-    let (input, _) = get_input(config.year, day, &config.database, config.test)?;
+    let input = match &config.input {
+        Some(input) => input.clone(),
+        None => {
+            get_input(
+                config.year,
+                day,
+                &config.database,
+                config.test,
+                config.session.as_ref(),
+                progress,
+            )?
+            .0
+        }
+    };
+    let input = config
+        .database
+        .get_normalization(config.year, day)?
+        .apply(input);
 
-    let input_clone = input.clone();
-    let start = std::time::Instant::now();
-    let first_result = if part == 1 {
-        solver.part1(input_clone)
-    } else {
-        solver.part2(input_clone)
+    let _guard = match config.benchmarks(day) && config.serial_benchmark {
+        true => Some(BENCHMARK_LOCK.lock().unwrap_or_else(|e| e.into_inner())),
+        false => None,
     };
-    let first_duration = start.elapsed();
 
-    let (warmup_runs, measurement_runs) = config.get_run_counts(first_duration);
+    progress.start(day, part);
+
+    let timeout = config.timeout(day);
+    let (first_result, first_duration) = run_part(solver, part, &input, timeout)?;
+
+    let (warmup_runs, measurement_runs) = config.get_run_counts(day, first_duration);
 
-    if measurement_runs > 1 {
+    let result = if measurement_runs > 1 {
         for _ in 0..warmup_runs {
-            let input_clone = input.clone();
-            if part == 1 {
-                solver.part1(input_clone);
-            } else {
-                solver.part2(input_clone);
-            }
+            run_part(solver, part, &input, timeout)?;
         }
 
         let mut durations = Vec::with_capacity(measurement_runs as usize);
         durations.push(first_duration);
 
         for _ in 1..measurement_runs {
-            let input_clone = input.clone();
-            let start = std::time::Instant::now();
-            if part == 1 {
-                solver.part1(input_clone);
-            } else {
-                solver.part2(input_clone);
-            }
-            durations.push(start.elapsed());
+            durations.push(run_part(solver, part, &input, timeout)?.1);
         }
 
-        tasks.insert(AocResult::new(day, part, first_result, durations));
+        AocResult::new(day, part as u8, first_result, durations)
     } else {
-        tasks.insert(AocResult::new(
-            day,
-            part,
-            first_result,
-            vec![first_duration],
-        ));
-    }
+        AocResult::new(day, part as u8, first_result, vec![first_duration])
+    };
+
+    progress.finish(&result);
+    tasks.insert(result);
 
     Ok(())
 }
 
-fn collect_results(tasks: HashSet<AocResult>) -> Result<AocYear, AocError> {
+/// Runs the part once and measures how long it took. With a timeout the part runs on its own
+/// thread. A solution can not be interrupted, so one exceeding the timeout is left running
+/// and an error is returned
+fn run_part(
+    solver: &Arc<dyn Solution>,
+    part: Parts,
+    input: &str,
+    timeout: Option<Duration>,
+) -> Result<(AocOption, Duration), AocError> {
+    let run = {
+        let solver = Arc::clone(solver);
+        let input = input.to_string();
+        move || {
+            let start = Instant::now();
+            let result = match part {
+                Parts::Part1 => solver.part1(input),
+                Parts::Part2 => solver.part2(input),
+            };
+            (result, start.elapsed())
+        }
+    };
+    let Some(timeout) = timeout else {
+        return Ok(run());
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let worker = thread::spawn(move || {
+        // Only fails if the timeout already passed
+        let _ = sender.send(run());
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => Ok(result),
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(AocError::Solution(SolutionError::Timeout {
+            seconds: timeout.as_secs(),
+        })),
+        // The solution panicked, pass the panic on
+        Err(mpsc::RecvTimeoutError::Disconnected) => match worker.join() {
+            Ok(()) => unreachable!("The result is sent before the worker finishes"),
+            Err(panic) => std::panic::resume_unwind(panic),
+        },
+    }
+}
+
+fn collect_results(year: i32, tasks: HashSet<AocResult>) -> AocYear {
     let mut results: Vec<AocResult> = tasks.into_iter().collect();
 
     results.sort_by_key(|r| (r.day(), r.part() as u8));
 
-    Ok(AocYear::from_vec(results))
+    AocYear::from_vec(year, results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, Cli};
+    use clap::Parser;
+    use std::{path::Path, sync::atomic::AtomicUsize};
+    use tempfile::TempDir;
+
+    fn run_config(data_dir: &Path, args: &[&str]) -> RunConfig {
+        let data_dir = data_dir.to_str().unwrap();
+        let cli = Cli::try_parse_from(
+            ["aou", "--data-dir", data_dir, "run", "2023", "1"]
+                .iter()
+                .chain(args),
+        )
+        .unwrap();
+        match Config::from_cli(cli).unwrap() {
+            Config::Run(mut config) => {
+                config.input = Some("input".to_string());
+                config
+            }
+            _ => unreachable!("Parsed a run command"),
+        }
+    }
+
+    /// Sleeps before answering and records how many calls overlapped
+    struct Sleeper {
+        delay: Duration,
+        active: Arc<AtomicUsize>,
+        overlap: Arc<AtomicUsize>,
+    }
+
+    impl Sleeper {
+        fn solve(&self, answer: i64) -> AocOption {
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.overlap.fetch_max(active, Ordering::SeqCst);
+            thread::sleep(self.delay);
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            AocOption::Int(answer)
+        }
+    }
+
+    impl Solution for Sleeper {
+        fn part1(&self, _input: String) -> AocOption {
+            self.solve(1)
+        }
+
+        fn part2(&self, _input: String) -> AocOption {
+            self.solve(2)
+        }
+    }
+
+    fn sleepers(days: u8, delay: impl Fn(u8) -> u64) -> (Vec<DayTask>, Arc<AtomicUsize>) {
+        let active = Arc::new(AtomicUsize::new(0));
+        let overlap = Arc::new(AtomicUsize::new(0));
+        let tasks = (1..=days)
+            .map(|day| {
+                let solver: Arc<dyn Solution> = Arc::new(Sleeper {
+                    delay: Duration::from_millis(delay(day)),
+                    active: Arc::clone(&active),
+                    overlap: Arc::clone(&overlap),
+                });
+                (day, solver)
+            })
+            .collect();
+        (tasks, overlap)
+    }
+
+    #[test]
+    fn parallel_days_are_complete_and_in_order() {
+        let dir = TempDir::new().unwrap();
+        let config = run_config(dir.path(), &["--jobs", "3"]);
+        // Earlier days take longer, so they finish last
+        let (days, overlap) = sleepers(4, |day| 40 - 10 * day as u64);
+
+        let year = run_days(&config, days).unwrap();
+        let order: Vec<(u8, Parts)> = year
+            .results()
+            .iter()
+            .map(|result| (result.day(), result.part()))
+            .collect();
+        let expected: Vec<(u8, Parts)> = (1..=4)
+            .flat_map(|day| [(day, Parts::Part1), (day, Parts::Part2)])
+            .collect();
+
+        assert!(order == expected);
+        assert!(overlap.load(Ordering::SeqCst) > 1);
+    }
+
+    #[test]
+    fn serial_benchmarks_never_overlap() {
+        let dir = TempDir::new().unwrap();
+        let args = ["--jobs", "4", "--benchmark", "--serial-benchmark"];
+        let config = run_config(dir.path(), &args);
+        let (days, overlap) = sleepers(4, |_| 2);
+
+        let year = run_days(&config, days).unwrap();
+
+        assert_eq!(year.results().len(), 8);
+        assert_eq!(overlap.load(Ordering::SeqCst), 1);
+    }
+
+    /// Fast on the first call and too slow afterwards
+    struct SlowsDown(AtomicUsize);
+
+    impl Solution for SlowsDown {
+        fn part1(&self, _input: String) -> AocOption {
+            if self.0.fetch_add(1, Ordering::SeqCst) > 0 {
+                thread::sleep(Duration::from_millis(1500));
+            }
+            AocOption::Int(1)
+        }
+
+        fn part2(&self, _input: String) -> AocOption {
+            AocOption::Int(2)
+        }
+    }
+
+    #[test]
+    fn every_benchmark_run_has_a_timeout() {
+        let dir = TempDir::new().unwrap();
+        let args = ["--benchmark", "--timeout", "1", "--part", "1"];
+        let config = run_config(dir.path(), &args);
+        let solver: Arc<dyn Solution> = Arc::new(SlowsDown(AtomicUsize::new(0)));
+
+        assert!(matches!(
+            run_days(&config, vec![(1, solver)]),
+            Err(AocError::Solution(SolutionError::Timeout { seconds: 1 }))
+        ));
+    }
 }
@@ -0,0 +1,145 @@
+use advent_of_utils_cli::{
+    error::{AocError, LoadingError, SolutionError},
+    types::{AocBenchmark, AocYear},
+};
+use sha2::{Digest, Sha256};
+use std::{fs, path::Path, process::Command};
+
+use crate::{config::RunConfig, loader::Solutions};
+
+/// Returns the commit hash of the git repository containing the workspace, if any
+fn commit_hash(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+/// Returns the SHA-256 hash of the solution library
+fn library_hash(path: &Path) -> Result<String, AocError> {
+    let bytes = fs::read(path).map_err(|e| {
+        AocError::Loading(LoadingError::invalid_library(format!(
+            "Failed reading {} for hashing: {}",
+            path.display(),
+            e
+        )))
+    })?;
+
+    Ok(Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Makes sure the baseline to compare against exists before running anything
+pub(crate) fn check_baseline(config: &RunConfig) -> Result<(), AocError> {
+    if let Some(baseline) = &config.baseline {
+        if !config.database.has_baseline(config.year, baseline)? {
+            return Err(AocError::UnknownBaseline {
+                year: config.year,
+                name: baseline.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Stores the benchmark run and returns its comparison against earlier runs if requested
+pub(crate) fn record(
+    config: &RunConfig,
+    solutions: &Solutions,
+    results: &AocYear,
+) -> Result<Option<AocBenchmark>, AocError> {
+    let db = &config.database;
+
+    let run = db.add_benchmark(
+        config.year,
+        commit_hash(&config.workspace_dir).as_deref(),
+        &library_hash(solutions.library_path())?,
+        config.save_baseline.as_deref(),
+        results,
+    )?;
+
+    if !config.compare {
+        return Ok(None);
+    }
+
+    let mut benchmark = AocBenchmark::new(config.threshold);
+    for result in results.results() {
+        let previous = match &config.baseline {
+            Some(baseline) => {
+                db.get_baseline_benchmark(config.year, result.day(), result.part(), baseline)?
+            }
+            None => db.get_previous_benchmark(config.year, result.day(), result.part(), run)?,
+        };
+        benchmark.push(result.day(), result.part(), previous, result.time().clone());
+    }
+
+    Ok(Some(benchmark))
+}
+
+/// Fails if a part got slower than the threshold allows
+pub(crate) fn check_regressions(benchmark: &AocBenchmark) -> Result<(), AocError> {
+    match benchmark.regressions() {
+        0 => Ok(()),
+        count => Err(AocError::Solution(SolutionError::Regression {
+            count,
+            threshold: benchmark.threshold(),
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, Cli};
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    fn run_config(data_dir: &Path, args: &[&str]) -> RunConfig {
+        let data_dir = data_dir.to_str().unwrap();
+        let cli = Cli::try_parse_from(
+            ["aou", "--data-dir", data_dir, "run", "2023", "1"]
+                .iter()
+                .chain(args),
+        )
+        .unwrap();
+        match Config::from_cli(cli).unwrap() {
+            Config::Run(config) => config,
+            _ => unreachable!("Parsed a run command"),
+        }
+    }
+
+    #[test]
+    fn unknown_baseline_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let args = ["--benchmark", "--compare", "--baseline", "before"];
+        let config = run_config(dir.path(), &args);
+
+        assert!(matches!(
+            check_baseline(&config),
+            Err(AocError::UnknownBaseline { year: 2023, ref name }) if name == "before"
+        ));
+
+        let results = AocYear::from_vec(2023, Vec::new());
+        config
+            .database
+            .add_benchmark(2023, None, "hash", Some("before"), &results)
+            .unwrap();
+        assert!(check_baseline(&config).is_ok());
+    }
+
+    #[test]
+    fn no_baseline_needs_no_check() {
+        let dir = TempDir::new().unwrap();
+        let config = run_config(dir.path(), &["--benchmark", "--compare"]);
+        assert!(check_baseline(&config).is_ok());
+    }
+}
@@ -1,18 +1,93 @@
-mod executor;
+pub(crate) mod executor;
+mod history;
 
-use advent_of_utils_cli::{error::AocError, types::display::Table};
+use advent_of_utils_cli::{
+    error::AocError,
+    types::display::{Export, OutputFormat},
+    types::{AocBenchmark, AocYear},
+};
 
 use crate::{config::RunConfig, loader};
 
 pub fn run(config: &RunConfig) -> Result<(), AocError> {
+    history::check_baseline(config)?;
+
     // Load solutions
     let solutions = loader::load_solutions(config)?;
 
     // Execute solutions
     let execution_result = executor::run_solutions(config, &solutions)?;
 
+    // Persist benchmarks and compare them, but not for a custom input
+    let benchmark = match config.benchmark && !config.test && config.input.is_none() {
+        true => history::record(config, &solutions, &execution_result)?,
+        false => None,
+    };
+
     // Display results with metrics
-    execution_result.table();
+    display(config.format, &execution_result, benchmark.as_ref())?;
+
+    match &benchmark {
+        Some(benchmark) => history::check_regressions(benchmark),
+        None => Ok(()),
+    }
+}
 
+/// Prints the results followed by the benchmark comparison. Machine-readable output stays a
+/// single document
+fn display(
+    format: OutputFormat,
+    results: &AocYear,
+    benchmark: Option<&AocBenchmark>,
+) -> Result<(), AocError> {
+    let Some(benchmark) = benchmark else {
+        return results.display(format);
+    };
+    match format {
+        OutputFormat::Json => println!("{}", report_json(results, benchmark)?),
+        // A second header would break the CSV, so the comparison goes to stderr
+        OutputFormat::Csv => {
+            print!("{}", results.to_csv());
+            eprint!("{}", benchmark.to_csv());
+        }
+        format => {
+            results.display(format)?;
+            benchmark.display(format)?;
+        }
+    }
     Ok(())
 }
+
+fn report_json(results: &AocYear, benchmark: &AocBenchmark) -> Result<String, AocError> {
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "results": results.results(),
+        "benchmark": benchmark,
+    }))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_utils::AocOption;
+    use advent_of_utils_cli::types::{AocResult, Parts};
+    use std::time::Duration;
+
+    #[test]
+    fn comparison_is_part_of_the_json_document() {
+        let time = vec![Duration::from_millis(2)];
+        let results = AocYear::from_vec(
+            2023,
+            vec![AocResult::new(1, 1, AocOption::Int(42), time.clone())],
+        );
+        let mut benchmark = AocBenchmark::new(10.0);
+        benchmark.push(1, Parts::Part1, None, results.results()[0].time().clone());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&report_json(&results, &benchmark).unwrap()).unwrap();
+
+        assert_eq!(json["results"][0]["answer"], "42");
+        assert_eq!(json["results"][0]["avg_ns"], 2_000_000);
+        assert_eq!(json["benchmark"][0]["status"], "New");
+        assert_eq!(json["benchmark"][0]["current_avg_ns"], 2_000_000);
+    }
+}
@@ -0,0 +1,59 @@
+use advent_of_utils_cli::{
+    error::{AocError, InputError},
+    input::{fetch_user, SessionToken},
+};
+use std::io::{stdin, IsTerminal};
+
+use crate::config::SessionConfig;
+
+/// Reads the session token without echoing it, or from stdin if it is piped in.
+/// It is never taken as an argument, which would keep it in the shell history
+fn read_token() -> Result<SessionToken, AocError> {
+    let token = match stdin().is_terminal() {
+        true => rpassword::prompt_password("Session token: "),
+        false => {
+            let mut token = String::new();
+            stdin().read_line(&mut token).map(|_| token)
+        }
+    }
+    .map_err(|_| AocError::Input(InputError::NoSession))?;
+
+    match token.trim() {
+        "" => Err(AocError::Input(InputError::NoSession)),
+        token => Ok(SessionToken::from(token.to_string())),
+    }
+}
+
+pub fn login(config: &SessionConfig) -> Result<(), AocError> {
+    let token = read_token()?;
+    let user = fetch_user(&token, &config.database)?;
+    config.database.set_session(&token.to_string())?;
+    println!(
+        "Logged in as {user} for profile {}",
+        config.database.profile()
+    );
+
+    Ok(())
+}
+
+pub fn logout(config: &SessionConfig) -> Result<(), AocError> {
+    match config.database.remove_session()? {
+        true => println!(
+            "Removed the stored session token of profile {}",
+            config.database.profile()
+        ),
+        false => println!(
+            "No session token was stored for profile {}",
+            config.database.profile()
+        ),
+    }
+
+    Ok(())
+}
+
+pub fn whoami(config: &SessionConfig) -> Result<(), AocError> {
+    let token = SessionToken::resolve(config.token.as_ref(), &config.database)?;
+    println!("{}", fetch_user(&token, &config.database)?);
+
+    Ok(())
+}
@@ -35,4 +35,41 @@ pub enum DatabaseError {
         #[source]
         source: io::Error,
     },
+
+    #[error("Failed restricting the permissions of {path}")]
+    PermissionsFailed {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error(
+        "The cache archive has version {version}, but only up to version {supported} is supported"
+    )]
+    UnsupportedArchive { version: u32, supported: u32 },
+
+    #[error("{count} cached inputs do not match their stored hash. Run 'aou cache clear <YEAR> <DAY>' to fetch them again")]
+    IntegrityFailed { count: usize },
+
+    #[error("Failed backing up the database to {path}")]
+    BackupFailed {
+        path: PathBuf,
+        #[source]
+        source: rusqlite::Error,
+    },
+
+    #[error("The database has schema version {version}, but this version of aou only supports up to {supported}. Update aou")]
+    SchemaTooNew { version: u32, supported: u32 },
+
+    #[error(
+        "Failed migrating the database to version {version} ({description}){}",
+        backup.as_ref().map(|path| format!(". The previous database was saved to {}", path.display())).unwrap_or_default()
+    )]
+    MigrationFailed {
+        version: u32,
+        description: String,
+        backup: Option<PathBuf>,
+        #[source]
+        source: Box<super::AocError>,
+    },
 }
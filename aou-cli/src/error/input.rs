@@ -11,6 +11,30 @@ pub enum InputError {
         source: Option<std::env::VarError>,
     },
 
+    #[error("No session token found. Pass --session, set $AOC_SESSION or run 'aou login'")]
+    NoSession,
+
+    #[error("The session token is invalid or expired. Run 'aou login' with a new token")]
+    InvalidSession,
+
+    #[error(
+        "The puzzle for year {year} day {day} is not released yet. Puzzles unlock at midnight EST"
+    )]
+    NotReleased { year: i32, day: u8 },
+
+    #[error("Advent of Code has no puzzle for year {year} day {day}")]
+    NotFound { year: i32, day: u8 },
+
+    #[error("Received an invalid input for year {year} day {day}: {reason}. Nothing was cached")]
+    InvalidInput { year: i32, day: u8, reason: String },
+
+    #[error("Request to {url} failed")]
+    RequestFailed {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
     #[error("Failed to fetch input for year {year} day {day}: {reason}")]
     FetchFailed {
         year: i32,
@@ -52,4 +76,18 @@ pub enum InputError {
         #[source]
         source: Option<Box<dyn std::error::Error + Send + Sync>>,
     },
+
+    #[error("Failed encrypting the input: {reason}")]
+    EncryptionFailed { reason: String },
+
+    #[error("Failed decrypting the input for year {year} day {day}. The passphrase or key file is wrong")]
+    DecryptionFailed { year: i32, day: u8 },
+
+    #[error(
+        "The input for year {year} day {day} is encrypted. Pass --key-file or set $AOU_PASSPHRASE"
+    )]
+    Encrypted { year: i32, day: u8 },
+
+    #[error("Failed to get private leaderboard {id} of {year}: {reason}")]
+    Leaderboard { year: i32, id: u64, reason: String },
 }
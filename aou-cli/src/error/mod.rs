@@ -1,13 +1,16 @@
 mod database;
 mod input;
 mod loading;
+mod output;
 mod solution;
 
 pub use database::DatabaseError;
 pub use input::InputError;
 pub use loading::LoadingError;
+pub use output::OutputError;
 pub use solution::SolutionError;
 
+use std::path::PathBuf;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -24,6 +27,9 @@ pub enum AocError {
     #[error("Database error: {0}")]
     Database(#[from] DatabaseError),
 
+    #[error("Output error: {0}")]
+    Output(#[from] OutputError),
+
     #[error("Invalid part number: {0}")]
     InvalidPart(u8),
 
@@ -32,4 +38,25 @@ pub enum AocError {
 
     #[error("Invalid day {day} for year {year}: {reason}")]
     InvalidDay { year: i32, day: u8, reason: String },
+
+    #[error("Invalid path pattern \"{pattern}\": {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+
+    #[error("Invalid configuration file {path}: {reason}")]
+    InvalidConfig { path: PathBuf, reason: String },
+
+    #[error("Invalid time \"{value}\": {reason}")]
+    InvalidTime { value: String, reason: String },
+
+    #[error("'{command}' only works on the database and can't be used with --storage-dir")]
+    UnsupportedStorage { command: String },
+
+    #[error("{flag} needs a single day")]
+    MissingDay { flag: String },
+
+    #[error("No benchmark baseline named \"{name}\" exists for year {year}")]
+    UnknownBaseline { year: i32, name: String },
+
+    #[error("Failed converting from or to JSON: {0}")]
+    Serialization(#[from] serde_json::Error),
 }
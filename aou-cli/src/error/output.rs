@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum OutputError {
+    #[error("Failed to read {path}")]
+    ReadFailed {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to write {path}")]
+    WriteFailed {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Missing marker {marker} in {path}. Add the start and end markers where the section should go")]
+    MissingMarker { path: PathBuf, marker: String },
+}
@@ -1,5 +1,24 @@
 use thiserror::Error;
 
+/// Joins the days, collapsing consecutive ones to ranges like `3-5, 9`
+fn list_days(days: &[u8]) -> String {
+    let mut ranges: Vec<(u8, u8)> = Vec::new();
+    for &day in days {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == day => *end = day,
+            _ => ranges.push((day, day)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|(start, end)| match start == end {
+            true => start.to_string(),
+            false => format!("{start}-{end}"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 #[derive(Error, Debug)]
 pub enum SolutionError {
     #[error("Solution failed: {msg}")]
@@ -15,6 +34,9 @@ pub enum SolutionError {
     #[error("Invalid solution result: {0}")]
     InvalidResult(String),
 
-    #[error("Solution not implemented")]
-    NotImplemented,
+    #[error("No solution implemented for year {year} day {}", list_days(days))]
+    NotImplemented { year: i32, days: Vec<u8> },
+
+    #[error("{count} part(s) got slower by more than {threshold}%")]
+    Regression { count: usize, threshold: f64 },
 }
@@ -1,21 +1,46 @@
 use crate::error::AocError;
-use crate::types::AocDatabase;
-use reqwest::blocking::Client;
-use std::{fmt::Display, sync::Arc, time::Instant};
+use crate::types::{display::Progress, AocDatabase, AocLeaderboard, DEFAULT_PROFILE};
+use chrono::Utc;
+use reqwest::{blocking::Client, StatusCode};
+use std::{
+    fmt::Display,
+    io::Read,
+    path::Path,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
 
 use crate::error::InputError;
 
 const USER_AGENT: &str =
     "Advent of Utils by Itron-al-Lenn found on github.com/Itron-al-Lenn/Advent-of-Utils";
 const AOC_BASE_URL: &str = "https://adventofcode.com";
+/// Overrides the base URL, e.g. to test against a local mock server
+const BASE_URL_VAR: &str = "AOU_BASE_URL";
+/// Minimum time between two requests, shared by all invocations through the database
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_BACKOFF: Duration = Duration::from_secs(2);
+/// Advent of Code asks to request a private leaderboard at most once every 15 minutes
+const LEADERBOARD_CACHE_TIME: Duration = Duration::from_secs(15 * 60);
+
+/// Held while a request is made so parallel runs also respect the minimum interval
+static REQUEST_LOCK: Mutex<()> = Mutex::new(());
 
 #[derive(Debug, Clone)]
 pub struct SessionToken(String);
 
 impl SessionToken {
+    /// Reads the session token from `$AOC_SESSION`
     pub fn new() -> Result<Self, InputError> {
         match std::env::var("AOC_SESSION") {
-            Ok(token) => Ok(Self(token)),
+            Ok(token) if !token.trim().is_empty() => Ok(Self(token.trim().to_string())),
+            Ok(_) => Err(InputError::VarError {
+                key: "AOC_SESSION".to_string(),
+                reason: "The session token is empty".to_string(),
+                source: None,
+            }),
             Err(e) => Err(InputError::VarError {
                 key: "AOC_SESSION".to_string(),
                 reason: "Failed fetching the session token".to_string(),
@@ -25,6 +50,25 @@ impl SessionToken {
     }
 }
 
+impl SessionToken {
+    /// Looks up the session token in the order: given token, `$AOC_SESSION`, stored token.
+    /// `$AOC_SESSION` is only used for the default profile.
+    pub fn resolve(token: Option<&SessionToken>, db: &AocDatabase) -> Result<Self, AocError> {
+        if let Some(token) = token {
+            return Ok(token.clone());
+        }
+        if db.profile() == DEFAULT_PROFILE {
+            if let Ok(token) = Self::new() {
+                return Ok(token);
+            }
+        }
+        match db.get_session()? {
+            Some(token) => Ok(Self(token)),
+            None => Err(AocError::Input(InputError::NoSession)),
+        }
+    }
+}
+
 impl Display for SessionToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -37,46 +81,202 @@ impl From<String> for SessionToken {
     }
 }
 
-fn create_client() -> Result<Client, InputError> {
-    let cookie = reqwest::cookie::Jar::default();
-    cookie.add_cookie_str(
-        &format!("session={}", SessionToken::new()?),
-        &AOC_BASE_URL.parse::<reqwest::Url>().unwrap(),
-    );
+fn base_url() -> String {
+    std::env::var(BASE_URL_VAR)
+        .ok()
+        .filter(|url| !url.is_empty())
+        .unwrap_or(AOC_BASE_URL.to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
 
-    Ok(Client::builder()
-        .cookie_provider(Arc::new(cookie))
-        .user_agent(USER_AGENT)
-        .build()
-        .expect("Failed to create HTTP client"))
+/// Whether a failed request is worth retrying
+fn is_transient(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
 }
 
-fn fetch_input(year: i32, day: u8) -> Result<String, InputError> {
-    let url = format!("{}/{}/day/{}/input", AOC_BASE_URL, year, day);
+/// The single way to talk to Advent of Code. Keeps the minimum interval between requests,
+/// retries transient failures and writes every request to the request log.
+pub struct AocClient<'a> {
+    client: Client,
+    db: &'a AocDatabase,
+    base_url: String,
+    min_interval: Duration,
+    backoff: Duration,
+}
 
-    create_client()?
-        .get(&url)
-        .send()
-        .map_err(|e| InputError::FetchFailed {
-            year,
-            day,
-            reason: "Network request failed".to_string(),
-            source: Some(e),
-        })?
-        .error_for_status()
-        .map_err(|e| InputError::FetchFailed {
+impl<'a> AocClient<'a> {
+    pub fn new(session: &SessionToken, db: &'a AocDatabase) -> Self {
+        let base_url = base_url();
+        let cookie = reqwest::cookie::Jar::default();
+        if let Ok(url) = base_url.parse::<reqwest::Url>() {
+            cookie.add_cookie_str(&format!("session={}", session), &url);
+        }
+
+        let client = Client::builder()
+            .cookie_provider(Arc::new(cookie))
+            .user_agent(USER_AGENT)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            db,
+            base_url,
+            min_interval: MIN_REQUEST_INTERVAL,
+            backoff: RETRY_BACKOFF,
+        }
+    }
+
+    /// Sleeps until the minimum interval since the last request has passed
+    fn wait_for_slot(&self) -> Result<(), AocError> {
+        if let Some(last) = self.db.last_request_time()? {
+            let elapsed = (Utc::now() - last).to_std().unwrap_or_default();
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends a GET request to the path and returns the status and body of the response
+    pub fn get(&self, path: &str) -> Result<(StatusCode, String), AocError> {
+        let url = format!("{}{}", self.base_url, path);
+        let _guard = REQUEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut attempt = 1;
+        loop {
+            self.wait_for_slot()?;
+            let response = self.client.get(&url).send().and_then(|response| {
+                let status = response.status();
+                response.text().map(|body| (status, body))
+            });
+
+            let logged = match &response {
+                Ok((status, _)) => self
+                    .db
+                    .log_request("GET", &url, Some(status.as_u16()), None),
+                Err(error) => self
+                    .db
+                    .log_request("GET", &url, None, Some(&error.to_string())),
+            };
+            // The request already went through, a missing log entry must not fail it
+            if let Err(error) = logged {
+                eprintln!("Failed logging the request to {}: {}", url, error);
+            }
+
+            match response {
+                Ok((status, _)) if is_transient(status) && attempt < MAX_ATTEMPTS => {}
+                Err(error)
+                    if (error.is_timeout() || error.is_connect()) && attempt < MAX_ATTEMPTS => {}
+                Ok(response) => return Ok(response),
+                Err(error) => {
+                    return Err(AocError::Input(InputError::RequestFailed {
+                        url,
+                        source: error,
+                    }))
+                }
+            }
+
+            thread::sleep(self.backoff * 2u32.pow(attempt - 1));
+            attempt += 1;
+        }
+    }
+}
+
+/// Validates the session token against the settings page and returns the account name
+pub fn fetch_user(session: &SessionToken, db: &AocDatabase) -> Result<String, AocError> {
+    let (status, page) = AocClient::new(session, db).get("/settings")?;
+    if !status.is_success() {
+        return Err(AocError::Input(InputError::InvalidSession));
+    }
+
+    let marker = "<div class=\"user\">";
+    let start = page
+        .find(marker)
+        .ok_or(AocError::Input(InputError::InvalidSession))?
+        + marker.len();
+    let name = page[start..]
+        .split('<')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+
+    match name.is_empty() {
+        true => Err(AocError::Input(InputError::InvalidSession)),
+        false => Ok(name),
+    }
+}
+
+/// Checks if a text is one of the pages Advent of Code answers with instead of an input
+fn is_error_page(text: &str) -> bool {
+    let start = text.trim_start();
+    text.contains("Please log in") || start.starts_with("<!DOCTYPE") || start.starts_with("<html")
+}
+
+/// Maps the response of the input endpoint to the input or the matching error
+fn validate_input(
+    year: i32,
+    day: u8,
+    status: StatusCode,
+    body: String,
+) -> Result<String, InputError> {
+    match status {
+        // AoC answers 400 without and 500 with an invalid session cookie
+        StatusCode::BAD_REQUEST | StatusCode::INTERNAL_SERVER_ERROR => {
+            Err(InputError::InvalidSession)
+        }
+        StatusCode::NOT_FOUND if body.contains("before it unlocks") => {
+            Err(InputError::NotReleased { year, day })
+        }
+        StatusCode::NOT_FOUND => Err(InputError::NotFound { year, day }),
+        status if !status.is_success() => Err(InputError::FetchFailed {
             year,
             day,
-            reason: "Server returned error status".to_string(),
-            source: Some(e),
-        })?
-        .text()
-        .map_err(|e| InputError::FetchFailed {
+            reason: format!("Server returned error status {}", status),
+            source: None,
+        }),
+        _ if body.contains("Please log in") => Err(InputError::InvalidSession),
+        _ if body.trim().is_empty() || is_error_page(&body) => Err(InputError::InvalidInput {
             year,
             day,
-            reason: "Failed to read response text".to_string(),
-            source: Some(e),
-        })
+            reason: "The response is not a puzzle input".to_string(),
+        }),
+        _ => Ok(body),
+    }
+}
+
+fn fetch_input(
+    year: i32,
+    day: u8,
+    session: &SessionToken,
+    db: &AocDatabase,
+) -> Result<String, AocError> {
+    let (status, body) =
+        AocClient::new(session, db).get(&format!("/{}/day/{}/input", year, day))?;
+
+    Ok(validate_input(year, day, status, body)?)
+}
+
+/// Reads an input from a file, or from stdin if the path is `-`. Nothing is cached
+pub fn read_input(path: &Path) -> Result<String, InputError> {
+    let mut input = String::new();
+    let result = match path.to_str() {
+        Some("-") => std::io::stdin().read_to_string(&mut input).map(|_| input),
+        _ => std::fs::read_to_string(path),
+    };
+    result.map_err(|error| InputError::FileReadError {
+        path: path.to_path_buf(),
+        reason: error.to_string(),
+        source: Some(error),
+    })
 }
 
 pub fn get_input(
@@ -84,14 +284,23 @@ pub fn get_input(
     day: u8,
     db: &AocDatabase,
     test: bool,
+    session: Option<&SessionToken>,
+    progress: &Progress,
 ) -> Result<(String, Instant), AocError> {
-    if db.has_input(year, day, test)? {
+    let cached = match db.has_input(year, day, test)? {
+        true => Some(db.get_input(year, day, test)?),
+        false => None,
+    };
+    // Error pages cached by earlier versions are fetched again
+    let cached = cached.filter(|input| test || !is_error_page(input));
+
+    if let Some(input) = cached {
         let time = Instant::now();
-        let input = db.get_input(year, day, test)?;
         Ok((input, time))
     } else if !test {
-        println!("Fetching online...");
-        let input = fetch_input(year, day)?;
+        progress.message("Fetching online...");
+        let session = SessionToken::resolve(session, db)?;
+        let input = fetch_input(year, day, &session, db)?;
         let time = Instant::now();
         db.set_input(year, day, test, input.clone())?;
         Ok((input, time))
@@ -99,3 +308,189 @@ pub fn get_input(
         Err(AocError::Input(InputError::NoTestInput { day }))
     }
 }
+
+/// Maps the response of a private leaderboard to the leaderboard or the matching error
+fn validate_leaderboard(
+    year: i32,
+    id: u64,
+    status: StatusCode,
+    body: &str,
+) -> Result<AocLeaderboard, InputError> {
+    let failed = |reason: String| InputError::Leaderboard { year, id, reason };
+    if !status.is_success() {
+        return Err(failed(format!("Server returned error status {}", status)));
+    }
+    // Without access Advent of Code redirects to the page listing your leaderboards
+    AocLeaderboard::from_json(year, body)
+        .map_err(|_| failed("It does not exist or the session has no access to it".to_string()))
+}
+
+/// Gets a private leaderboard. It is fetched again only if the cached one is older than
+/// 15 minutes
+pub fn get_leaderboard(
+    year: i32,
+    id: u64,
+    db: &AocDatabase,
+    session: Option<&SessionToken>,
+    progress: &Progress,
+) -> Result<AocLeaderboard, AocError> {
+    if let Some((fetched_at, body)) = db.get_leaderboard(year, id)? {
+        let age = (Utc::now() - fetched_at).to_std().unwrap_or_default();
+        if age < LEADERBOARD_CACHE_TIME {
+            return Ok(validate_leaderboard(year, id, StatusCode::OK, &body)?);
+        }
+    }
+
+    progress.message("Fetching online...");
+    let session = SessionToken::resolve(session, db)?;
+    let (status, body) = AocClient::new(&session, db)
+        .get(&format!("/{}/leaderboard/private/view/{}.json", year, id))?;
+    let leaderboard = validate_leaderboard(year, id, status, &body)?;
+    db.set_leaderboard(year, id, &body)?;
+    Ok(leaderboard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn responses_map_to_errors() {
+        let cases = [
+            (StatusCode::BAD_REQUEST, "", "InvalidSession"),
+            (StatusCode::INTERNAL_SERVER_ERROR, "", "InvalidSession"),
+            (
+                StatusCode::NOT_FOUND,
+                "Please don't repeatedly request this endpoint before it unlocks!",
+                "NotReleased",
+            ),
+            (StatusCode::NOT_FOUND, "404 Not Found", "NotFound"),
+            (StatusCode::FORBIDDEN, "", "FetchFailed"),
+            (
+                StatusCode::OK,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.",
+                "InvalidSession",
+            ),
+            (StatusCode::OK, "  \n", "InvalidInput"),
+            (
+                StatusCode::OK,
+                "<!DOCTYPE html>\n<html></html>",
+                "InvalidInput",
+            ),
+            (StatusCode::OK, "<html><body></body></html>", "InvalidInput"),
+        ];
+
+        for (status, body, expected) in cases {
+            let error = validate_input(2023, 5, status, body.to_string()).unwrap_err();
+            let matched = match error {
+                InputError::InvalidSession => "InvalidSession",
+                InputError::NotReleased { year: 2023, day: 5 } => "NotReleased",
+                InputError::NotFound { year: 2023, day: 5 } => "NotFound",
+                InputError::FetchFailed { .. } => "FetchFailed",
+                InputError::InvalidInput { .. } => "InvalidInput",
+                _ => "other",
+            };
+            assert_eq!(matched, expected, "{status} {body:?}");
+        }
+    }
+
+    #[test]
+    fn puzzle_input_is_returned_unchanged() {
+        let input = "1721\n979\n366\n";
+
+        assert_eq!(
+            validate_input(2020, 1, StatusCode::OK, input.to_string()).unwrap(),
+            input
+        );
+    }
+
+    /// Answers one request per status on a local port and returns the times they arrived
+    fn serve(statuses: Vec<u16>) -> (String, thread::JoinHandle<Vec<Instant>>) {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            statuses
+                .into_iter()
+                .map(|status| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let arrived = Instant::now();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                        line.clear();
+                    }
+                    write!(
+                        stream,
+                        "HTTP/1.1 {} Status\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+                        status
+                    )
+                    .unwrap();
+                    arrived
+                })
+                .collect()
+        });
+        (url, server)
+    }
+
+    fn client<'a>(db: &'a AocDatabase, url: &str) -> AocClient<'a> {
+        let mut client = AocClient::new(&SessionToken::from("token".to_string()), db);
+        client.base_url = url.to_string();
+        client.min_interval = Duration::ZERO;
+        client.backoff = Duration::from_millis(10);
+        client
+    }
+
+    #[test]
+    fn requests_keep_the_minimum_interval() {
+        let db = AocDatabase::in_memory().unwrap();
+        let (url, server) = serve(vec![200, 200]);
+
+        // The interval is kept through the request log, so it also holds across clients
+        for _ in 0..2 {
+            let mut client = client(&db, &url);
+            client.min_interval = Duration::from_millis(300);
+            assert_eq!(client.get("/").unwrap().0, StatusCode::OK);
+        }
+
+        let arrived = server.join().unwrap();
+        assert!(arrived[1] - arrived[0] >= Duration::from_millis(250));
+    }
+
+    #[test]
+    fn transient_failures_are_retried() {
+        let db = AocDatabase::in_memory().unwrap();
+        let (url, server) = serve(vec![503, 502, 200]);
+
+        assert_eq!(
+            client(&db, &url).get("/").unwrap(),
+            (StatusCode::OK, "ok".to_string())
+        );
+        assert_eq!(server.join().unwrap().len(), 3);
+        assert_eq!(db.get_requests(10).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn retries_give_up() {
+        let db = AocDatabase::in_memory().unwrap();
+        let (url, server) = serve(vec![503; MAX_ATTEMPTS as usize]);
+
+        assert_eq!(
+            client(&db, &url).get("/").unwrap().0,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(server.join().unwrap().len(), MAX_ATTEMPTS as usize);
+
+        // Nothing listens on the port anymore
+        assert!(matches!(
+            client(&db, &url).get("/"),
+            Err(AocError::Input(InputError::RequestFailed { .. }))
+        ));
+        assert_eq!(
+            db.get_requests(10).unwrap().len(),
+            2 * MAX_ATTEMPTS as usize
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use serde::{Serialize, Serializer};
+
+use crate::error::AocError;
+
+use super::{
+    display::{Export, Table, TableStruct},
+    time::AocDuration,
+    Parts,
+};
+
+/// A benchmarked part together with the measurement it is compared against
+struct AocComparison {
+    day: u8,
+    part: Parts,
+    previous: Option<AocDuration>,
+    current: AocDuration,
+}
+
+impl AocComparison {
+    /// Relative change of the average time in percent
+    fn delta(&self) -> Option<f64> {
+        let previous = self.previous.as_ref()?.avg_time()?.as_secs_f64();
+        let current = self.current.avg_time()?.as_secs_f64();
+        if previous == 0.0 {
+            return None;
+        }
+        Some((current - previous) / previous * 100.0)
+    }
+}
+
+/// Comparison of a benchmark run against an earlier run or a named baseline
+pub struct AocBenchmark {
+    comparisons: Vec<AocComparison>,
+    threshold: f64,
+}
+
+impl AocBenchmark {
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            comparisons: Vec::new(),
+            threshold,
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        day: u8,
+        part: Parts,
+        previous: Option<AocDuration>,
+        current: AocDuration,
+    ) {
+        self.comparisons.push(AocComparison {
+            day,
+            part,
+            previous,
+            current,
+        });
+    }
+
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Number of parts which got slower by more than the threshold
+    pub fn regressions(&self) -> usize {
+        self.comparisons
+            .iter()
+            .filter(|c| self.is_regression(c))
+            .count()
+    }
+
+    fn is_regression(&self, comparison: &AocComparison) -> bool {
+        comparison
+            .delta()
+            .is_some_and(|delta| delta > self.threshold)
+    }
+
+    fn status(&self, comparison: &AocComparison) -> String {
+        match comparison.delta() {
+            None => "New".to_string(),
+            Some(_) if self.is_regression(comparison) => "Regression".to_string(),
+            Some(delta) if delta < -self.threshold => "Improved".to_string(),
+            Some(_) => "Unchanged".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct AocComparisonRecord {
+    day: u8,
+    part: u8,
+    previous_avg_ns: Option<u64>,
+    current_avg_ns: Option<u64>,
+    delta_percent: Option<f64>,
+    status: String,
+}
+
+impl AocBenchmark {
+    fn records(&self) -> Vec<AocComparisonRecord> {
+        self.comparisons
+            .iter()
+            .map(|c| AocComparisonRecord {
+                day: c.day,
+                part: c.part.as_number(),
+                previous_avg_ns: c
+                    .previous
+                    .as_ref()
+                    .and_then(|p| p.avg_time())
+                    .map(|d| d.as_nanos() as u64),
+                current_avg_ns: c.current.avg_time().map(|d| d.as_nanos() as u64),
+                delta_percent: c.delta(),
+                status: self.status(c),
+            })
+            .collect()
+    }
+}
+
+impl Serialize for AocBenchmark {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.records().serialize(serializer)
+    }
+}
+
+fn format_delta(delta: Option<f64>) -> String {
+    match delta {
+        Some(delta) => format!("{:+.2} %", delta),
+        None => "None".to_string(),
+    }
+}
+
+impl Table for AocBenchmark {
+    fn table_constructor(&self) -> TableStruct {
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "Day".to_string(),
+            "Part".to_string(),
+            "Previous".to_string(),
+            "Current".to_string(),
+            "Delta".to_string(),
+            "Status".to_string(),
+        ]];
+        for comparison in self.comparisons.iter() {
+            contents.push(vec![
+                comparison.day.to_string(),
+                comparison.part.as_number().to_string(),
+                comparison.previous.clone().unwrap_or_default().to_string(),
+                comparison.current.to_string(),
+                format_delta(comparison.delta()),
+                self.status(comparison),
+            ])
+        }
+        TableStruct::new(contents)
+    }
+
+    fn reduced_table_constructor(&self) -> TableStruct {
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "Day".to_string(),
+            "Part".to_string(),
+            "Delta".to_string(),
+        ]];
+        for comparison in self.comparisons.iter() {
+            contents.push(vec![
+                comparison.day.to_string(),
+                comparison.part.as_number().to_string(),
+                format_delta(comparison.delta()),
+            ])
+        }
+        TableStruct::new(contents)
+    }
+}
+
+impl Export for AocBenchmark {
+    fn to_json(&self) -> Result<String, AocError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    fn to_csv(&self) -> String {
+        let mut output =
+            "day,part,previous_avg_ns,current_avg_ns,delta_percent,status\n".to_string();
+        for record in self.records() {
+            output.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                record.day,
+                record.part,
+                record
+                    .previous_avg_ns
+                    .map(|t| t.to_string())
+                    .unwrap_or_default(),
+                record
+                    .current_avg_ns
+                    .map(|t| t.to_string())
+                    .unwrap_or_default(),
+                record
+                    .delta_percent
+                    .map(|d| format!("{:.2}", d))
+                    .unwrap_or_default(),
+                record.status,
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn seconds(seconds: u64) -> AocDuration {
+        AocDuration::new(vec![Duration::from_secs(seconds)])
+    }
+
+    fn benchmark(threshold: f64, previous: Option<u64>, current: u64) -> AocBenchmark {
+        let mut benchmark = AocBenchmark::new(threshold);
+        benchmark.push(1, Parts::Part1, previous.map(seconds), seconds(current));
+        benchmark
+    }
+
+    fn status(benchmark: &AocBenchmark) -> String {
+        benchmark.status(&benchmark.comparisons[0])
+    }
+
+    #[test]
+    fn slowdown_at_threshold_is_no_regression() {
+        let at_threshold = benchmark(25.0, Some(4), 5);
+        assert_eq!(at_threshold.comparisons[0].delta(), Some(25.0));
+        assert_eq!(at_threshold.regressions(), 0);
+        assert_eq!(status(&at_threshold), "Unchanged");
+
+        let above_threshold = benchmark(24.9, Some(4), 5);
+        assert_eq!(above_threshold.regressions(), 1);
+        assert_eq!(status(&above_threshold), "Regression");
+    }
+
+    #[test]
+    fn faster_run_is_improved() {
+        let faster = benchmark(10.0, Some(5), 4);
+        assert_eq!(faster.comparisons[0].delta(), Some(-20.0));
+        assert_eq!(faster.regressions(), 0);
+        assert_eq!(status(&faster), "Improved");
+    }
+
+    #[test]
+    fn missing_previous_run_is_new() {
+        let new = benchmark(10.0, None, 4);
+        assert_eq!(new.comparisons[0].delta(), None);
+        assert_eq!(new.regressions(), 0);
+        assert_eq!(status(&new), "New");
+    }
+}
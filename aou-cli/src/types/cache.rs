@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+
+use super::display::{Table, TableStruct};
+
+/// Version of the archive layout written by `aou cache export`
+pub const CACHE_ARCHIVE_VERSION: u32 = 1;
+
+/// What is cached for a single day of a profile
+pub struct AocCacheEntry {
+    pub year: i32,
+    pub day: u8,
+    pub input: bool,
+    pub test_input: bool,
+    pub part_1: bool,
+    pub part_2: bool,
+}
+
+/// Overview of the cached inputs and answers of a profile
+pub struct AocCache {
+    entries: Vec<AocCacheEntry>,
+}
+
+impl AocCache {
+    pub fn new(entries: Vec<AocCacheEntry>) -> Self {
+        Self { entries }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn mark(cached: bool) -> String {
+    match cached {
+        true => "✓".to_string(),
+        false => String::new(),
+    }
+}
+
+impl Table for AocCache {
+    fn table_constructor(&self) -> TableStruct {
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "Year".to_string(),
+            "Day".to_string(),
+            "Input".to_string(),
+            "Test Input".to_string(),
+            "Answer 1".to_string(),
+            "Answer 2".to_string(),
+        ]];
+        for entry in self.entries.iter() {
+            contents.push(vec![
+                entry.year.to_string(),
+                entry.day.to_string(),
+                mark(entry.input),
+                mark(entry.test_input),
+                mark(entry.part_1),
+                mark(entry.part_2),
+            ])
+        }
+        TableStruct::new(contents)
+    }
+
+    fn reduced_table_constructor(&self) -> TableStruct {
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "Year".to_string(),
+            "Day".to_string(),
+            "Input".to_string(),
+        ]];
+        for entry in self.entries.iter() {
+            contents.push(vec![
+                entry.year.to_string(),
+                entry.day.to_string(),
+                mark(entry.input),
+            ])
+        }
+        TableStruct::new(contents)
+    }
+}
+
+/// Result of comparing a cached input against its stored hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputStatus {
+    Valid,
+    Mismatch,
+    /// Stored before hashes were recorded
+    Unhashed,
+}
+
+impl std::fmt::Display for InputStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputStatus::Valid => write!(f, "OK"),
+            InputStatus::Mismatch => write!(f, "Hash mismatch"),
+            InputStatus::Unhashed => write!(f, "No hash"),
+        }
+    }
+}
+
+/// Integrity check of a single cached input
+pub struct AocInputCheck {
+    pub year: i32,
+    pub day: u8,
+    pub fetched_at: Option<String>,
+    pub status: InputStatus,
+}
+
+/// Integrity checks of all cached inputs of a profile
+pub struct AocInputReport {
+    checks: Vec<AocInputCheck>,
+}
+
+impl AocInputReport {
+    pub fn new(checks: Vec<AocInputCheck>) -> Self {
+        Self { checks }
+    }
+
+    pub fn count(&self, status: InputStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+}
+
+impl Table for AocInputReport {
+    fn table_constructor(&self) -> TableStruct {
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "Year".to_string(),
+            "Day".to_string(),
+            "Fetched".to_string(),
+            "Status".to_string(),
+        ]];
+        for check in self.checks.iter() {
+            contents.push(vec![
+                check.year.to_string(),
+                check.day.to_string(),
+                check.fetched_at.clone().unwrap_or_default(),
+                check.status.to_string(),
+            ])
+        }
+        TableStruct::new(contents)
+    }
+
+    fn reduced_table_constructor(&self) -> TableStruct {
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "Year".to_string(),
+            "Day".to_string(),
+            "Status".to_string(),
+        ]];
+        for check in self.checks.iter() {
+            contents.push(vec![
+                check.year.to_string(),
+                check.day.to_string(),
+                check.status.to_string(),
+            ])
+        }
+        TableStruct::new(contents)
+    }
+}
+
+/// A row of the `Inputs` table in a cache archive
+#[derive(Serialize, Deserialize)]
+pub struct ArchivedInput {
+    pub profile: String,
+    pub year: i32,
+    pub day: u8,
+    pub input: Option<String>,
+    pub test_input: Option<String>,
+    #[serde(default)]
+    pub input_hash: Option<String>,
+    #[serde(default)]
+    pub fetched_at: Option<String>,
+}
+
+/// A row of the `Results` or `Test_Results` table in a cache archive
+#[derive(Serialize, Deserialize)]
+pub struct ArchivedResult {
+    pub profile: String,
+    pub year: i32,
+    pub day: u8,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+/// Portable copy of the cached inputs and answers of all profiles.
+/// Session tokens and the request log are never part of it.
+#[derive(Serialize, Deserialize)]
+pub struct AocCacheArchive {
+    pub version: u32,
+    pub inputs: Vec<ArchivedInput>,
+    pub results: Vec<ArchivedResult>,
+    pub test_results: Vec<ArchivedResult>,
+}
@@ -0,0 +1,202 @@
+use std::collections::HashSet;
+
+use super::{
+    display::{Table, TableStruct},
+    AocYear, Parts,
+};
+
+pub(crate) const STAR: &str = "⭐";
+const DAYS_PER_ROW: u8 = 5;
+const CELL_WIDTH: u32 = 64;
+const CELL_HEIGHT: u32 = 48;
+const HEADER_HEIGHT: u32 = 40;
+
+/// The stars earned in a year together with the timings of the solutions
+pub struct AocCalendar {
+    year: i32,
+    days: u8,
+    stars: HashSet<(u8, Parts)>,
+    results: AocYear,
+}
+
+impl AocCalendar {
+    pub fn new(year: i32, days: u8, stars: HashSet<(u8, Parts)>, results: AocYear) -> Self {
+        Self {
+            year,
+            days,
+            stars,
+            results,
+        }
+    }
+
+    pub fn has_star(&self, day: u8, part: Parts) -> bool {
+        self.stars.contains(&(day, part))
+    }
+
+    pub fn star_count(&self) -> usize {
+        self.stars.len()
+    }
+
+    fn star_cell(&self, day: u8, part: Parts) -> String {
+        match self.has_star(day, part) {
+            true => STAR.to_string(),
+            false => String::new(),
+        }
+    }
+
+    fn time_cell(&self, day: u8, part: Parts) -> String {
+        self.results
+            .results()
+            .into_iter()
+            .find(|r| r.day() == day && r.part() == part)
+            .map(|r| r.time().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Progress badge linking to the year on Advent of Code
+    pub fn badge(&self) -> String {
+        format!(
+            "[![{year}](https://img.shields.io/badge/{year}-{stars}%2F{total}%20stars-yellow)](https://adventofcode.com/{year})",
+            year = self.year,
+            stars = self.star_count(),
+            total = self.days as usize * 2,
+        )
+    }
+
+    /// Badge followed by the star and timing table
+    pub fn markdown(&self, svg: Option<&str>) -> String {
+        let mut output = format!("{}\n\n", self.badge());
+        if let Some(svg) = svg {
+            output.push_str(&format!("![Advent of Code {}]({})\n\n", self.year, svg));
+        }
+        output.push_str(&self.table_constructor().markdown());
+        output
+    }
+
+    /// Calendar of all days with one star per part
+    pub fn svg(&self) -> String {
+        let rows = self.days.div_ceil(DAYS_PER_ROW) as u32;
+        let width = DAYS_PER_ROW as u32 * CELL_WIDTH;
+        let height = HEADER_HEIGHT + rows * CELL_HEIGHT;
+
+        let mut output = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" font-family=\"monospace\">\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"#0f0f23\"/>\n\
+             <text x=\"{x}\" y=\"26\" fill=\"#00cc00\" font-size=\"18\" text-anchor=\"middle\">Advent of Code {year}: {stars}/{total} *</text>\n",
+            x = width / 2,
+            year = self.year,
+            stars = self.star_count(),
+            total = self.days as usize * 2,
+        );
+
+        for day in 1..=self.days {
+            let index = (day - 1) as u32;
+            let x = (index % DAYS_PER_ROW as u32) * CELL_WIDTH;
+            let y = HEADER_HEIGHT + (index / DAYS_PER_ROW as u32) * CELL_HEIGHT;
+            let color = |part| match self.has_star(day, part) {
+                true => "#ffff66",
+                false => "#333340",
+            };
+            output.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" fill=\"#cccccc\" font-size=\"14\" text-anchor=\"middle\">{}</text>\n\
+                 <text x=\"{}\" y=\"{}\" fill=\"{}\" font-size=\"18\" text-anchor=\"middle\">*</text>\n\
+                 <text x=\"{}\" y=\"{}\" fill=\"{}\" font-size=\"18\" text-anchor=\"middle\">*</text>\n",
+                x + CELL_WIDTH / 2,
+                y + 18,
+                day,
+                x + CELL_WIDTH / 2 - 10,
+                y + 40,
+                color(Parts::Part1),
+                x + CELL_WIDTH / 2 + 10,
+                y + 40,
+                color(Parts::Part2),
+            ));
+        }
+
+        output.push_str("</svg>\n");
+        output
+    }
+}
+
+impl Table for AocCalendar {
+    fn table_constructor(&self) -> TableStruct {
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "Day".to_string(),
+            "Part 1".to_string(),
+            "Part 2".to_string(),
+            "Avg. Time 1".to_string(),
+            "Avg. Time 2".to_string(),
+        ]];
+        for day in 1..=self.days {
+            contents.push(vec![
+                day.to_string(),
+                self.star_cell(day, Parts::Part1),
+                self.star_cell(day, Parts::Part2),
+                self.time_cell(day, Parts::Part1),
+                self.time_cell(day, Parts::Part2),
+            ])
+        }
+        TableStruct::new(contents)
+    }
+
+    fn reduced_table_constructor(&self) -> TableStruct {
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "Day".to_string(),
+            "Part 1".to_string(),
+            "Part 2".to_string(),
+        ]];
+        for day in 1..=self.days {
+            contents.push(vec![
+                day.to_string(),
+                self.star_cell(day, Parts::Part1),
+                self.star_cell(day, Parts::Part2),
+            ])
+        }
+        TableStruct::new(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calendar() -> AocCalendar {
+        let stars = [(1, Parts::Part1), (1, Parts::Part2), (2, Parts::Part1)];
+        AocCalendar::new(
+            2025,
+            12,
+            stars.into_iter().collect(),
+            AocYear::from_vec(2025, vec![]),
+        )
+    }
+
+    #[test]
+    fn markdown_has_badge_image_and_table() {
+        let markdown = calendar().markdown(Some("cal.svg"));
+        let lines: Vec<&str> = markdown.lines().collect();
+
+        assert!(lines[0].contains("2025-3%2F24%20stars"));
+        assert_eq!(lines[2], "![Advent of Code 2025](cal.svg)");
+        assert_eq!(
+            lines[4],
+            "| Day | Part 1 | Part 2 | Avg. Time 1 | Avg. Time 2 |"
+        );
+        assert_eq!(lines[6], "| 1 | ⭐ | ⭐ |  |  |");
+        assert_eq!(lines[7], "| 2 | ⭐ |  |  |  |");
+        assert_eq!(lines.len(), 6 + 12);
+        assert!(!calendar().markdown(None).contains("![Advent of Code"));
+    }
+
+    #[test]
+    fn svg_colors_earned_stars() {
+        let svg = calendar().svg();
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains("Advent of Code 2025: 3/24 *"));
+        assert_eq!(svg.matches("fill=\"#ffff66\"").count(), 3);
+        assert_eq!(svg.matches("fill=\"#333340\"").count(), 21);
+        // 12 days in rows of 5
+        assert!(svg.contains("height=\"184\""));
+    }
+}
@@ -0,0 +1,222 @@
+use rusqlite::{params, OptionalExtension};
+use std::time::Duration;
+
+use crate::{
+    error::{AocError, DatabaseError},
+    types::{AocDuration, AocYear},
+    Parts,
+};
+
+use super::AocDatabase;
+
+fn encode_samples(time: &AocDuration) -> String {
+    time.duration()
+        .iter()
+        .map(|d| d.as_nanos().to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn decode_samples(samples: &str) -> AocDuration {
+    AocDuration::new(
+        samples
+            .split(',')
+            .filter_map(|s| s.parse::<u64>().ok())
+            .map(Duration::from_nanos)
+            .collect(),
+    )
+}
+
+impl AocDatabase {
+    pub(super) fn create_benchmarks(&self) -> Result<(), AocError> {
+        self.execute(
+            "CREATE TABLE IF NOT EXISTS Benchmarks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                profile TEXT NOT NULL DEFAULT 'default',
+                year INTEGER NOT NULL,
+                timestamp TEXT NOT NULL,
+                commit_hash TEXT,
+                library_hash TEXT NOT NULL,
+                baseline TEXT,
+                UNIQUE(profile, year, baseline)
+            )",
+            [],
+        )?;
+
+        if !self.has_column("Benchmarks", "profile")? {
+            self.execute(
+                "ALTER TABLE Benchmarks ADD COLUMN profile TEXT NOT NULL DEFAULT 'default'",
+                [],
+            )?;
+        }
+
+        self.execute(
+            "CREATE TABLE IF NOT EXISTS Benchmark_Results (
+                run INTEGER NOT NULL REFERENCES Benchmarks(id) ON DELETE CASCADE,
+                day INTEGER NOT NULL,
+                part INTEGER NOT NULL,
+                result TEXT NOT NULL,
+                samples TEXT NOT NULL,
+                PRIMARY KEY(run, day, part)
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// Stores all results of a benchmark run and returns the id of the run
+    pub fn add_benchmark(
+        &self,
+        year: i32,
+        commit_hash: Option<&str>,
+        library_hash: &str,
+        baseline: Option<&str>,
+        results: &AocYear,
+    ) -> Result<i64, AocError> {
+        let mut conn = self.get_conn()?;
+        let transaction = conn.transaction().map_err(|error| {
+            AocError::Database(DatabaseError::DatabaseExec {
+                command: "BEGIN TRANSACTION".to_string(),
+                source: error,
+            })
+        })?;
+
+        if let Some(baseline) = baseline {
+            let command = "UPDATE Benchmarks SET baseline = NULL
+                           WHERE profile = ?1 AND year = ?2 AND baseline = ?3";
+            transaction
+                .execute(command, params![self.profile, year, baseline])
+                .map_err(|error| {
+                    AocError::Database(DatabaseError::DatabaseExec {
+                        command: command.to_string(),
+                        source: error,
+                    })
+                })?;
+        }
+
+        let command =
+            "INSERT INTO Benchmarks (profile, year, timestamp, commit_hash, library_hash, baseline)
+                       VALUES (?1, ?2, ?3, ?4, ?5, ?6)";
+        transaction
+            .execute(
+                command,
+                params![
+                    self.profile,
+                    year,
+                    chrono::Utc::now().to_rfc3339(),
+                    commit_hash,
+                    library_hash,
+                    baseline
+                ],
+            )
+            .map_err(|error| {
+                AocError::Database(DatabaseError::DatabaseExec {
+                    command: command.to_string(),
+                    source: error,
+                })
+            })?;
+        let run = transaction.last_insert_rowid();
+
+        let command = "INSERT INTO Benchmark_Results (run, day, part, result, samples)
+                       VALUES (?1, ?2, ?3, ?4, ?5)";
+        for result in results.results() {
+            transaction
+                .execute(
+                    command,
+                    params![
+                        run,
+                        result.day(),
+                        result.part().as_number(),
+                        result.result().to_string(),
+                        encode_samples(result.time())
+                    ],
+                )
+                .map_err(|error| {
+                    AocError::Database(DatabaseError::DatabaseExec {
+                        command: command.to_string(),
+                        source: error,
+                    })
+                })?;
+        }
+
+        transaction.commit().map_err(|error| {
+            AocError::Database(DatabaseError::DatabaseExec {
+                command: "COMMIT".to_string(),
+                source: error,
+            })
+        })?;
+
+        Ok(run)
+    }
+
+    /// Gets the latest measurement of a part recorded before the given run
+    pub fn get_previous_benchmark(
+        &self,
+        year: i32,
+        day: u8,
+        part: Parts,
+        before: i64,
+    ) -> Result<Option<AocDuration>, AocError> {
+        let conn = self.get_conn()?;
+        conn.query_row(
+            "SELECT samples FROM Benchmark_Results
+             JOIN Benchmarks ON run = id
+             WHERE profile = ?1 AND year = ?2 AND day = ?3 AND part = ?4 AND id < ?5
+             ORDER BY id DESC
+             LIMIT 1",
+            params![self.profile, year, day, part.as_number(), before],
+            |row| row.get::<usize, String>(0),
+        )
+        .optional()
+        .map(|samples| samples.as_deref().map(decode_samples))
+        .map_err(|error| {
+            AocError::Database(DatabaseError::DatabaseQuerying {
+                object: "Benchmark".to_string(),
+                source: error,
+            })
+        })
+    }
+
+    /// Gets the measurement of a part stored in the named baseline
+    pub fn get_baseline_benchmark(
+        &self,
+        year: i32,
+        day: u8,
+        part: Parts,
+        baseline: &str,
+    ) -> Result<Option<AocDuration>, AocError> {
+        let conn = self.get_conn()?;
+        conn.query_row(
+            "SELECT samples FROM Benchmark_Results
+             JOIN Benchmarks ON run = id
+             WHERE profile = ?1 AND year = ?2 AND day = ?3 AND part = ?4 AND baseline = ?5",
+            params![self.profile, year, day, part.as_number(), baseline],
+            |row| row.get::<usize, String>(0),
+        )
+        .optional()
+        .map(|samples| samples.as_deref().map(decode_samples))
+        .map_err(|error| {
+            AocError::Database(DatabaseError::DatabaseQuerying {
+                object: "Benchmark".to_string(),
+                source: error,
+            })
+        })
+    }
+
+    pub fn has_baseline(&self, year: i32, baseline: &str) -> Result<bool, AocError> {
+        let conn = self.get_conn()?;
+        match conn.query_row(
+            "SELECT COUNT(*) FROM Benchmarks
+             WHERE profile = ?1 AND year = ?2 AND baseline = ?3",
+            params![self.profile, year, baseline],
+            |row| row.get::<usize, i32>(0),
+        ) {
+            Ok(count) => Ok(count > 0),
+            Err(error) => Err(AocError::Database(DatabaseError::DatabaseQuerying {
+                object: "Baseline".to_string(),
+                source: error,
+            })),
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use rusqlite::{params, Row};
+
+use crate::{
+    error::{AocError, DatabaseError},
+    types::{
+        cache::{ArchivedInput, ArchivedResult, CACHE_ARCHIVE_VERSION},
+        AocCacheArchive, AocCacheEntry, AocInputCheck, InputStatus,
+    },
+};
+
+use super::{tables::input_hash, AocDatabase, DEFAULT_PROFILE};
+
+fn archived_result(row: &Row) -> rusqlite::Result<ArchivedResult> {
+    Ok(ArchivedResult {
+        profile: row.get(0)?,
+        year: row.get(1)?,
+        day: row.get(2)?,
+        part_1: row.get(3)?,
+        part_2: row.get(4)?,
+    })
+}
+
+impl AocDatabase {
+    /// Lists which inputs and answers are cached for the days of the profile
+    pub fn list_cache(&self, year: Option<i32>) -> Result<Vec<AocCacheEntry>, AocError> {
+        self.storage().list(&self.profile, year)
+    }
+
+    /// Removes the cached inputs of the profile, and its stored answers if `answers` is set.
+    /// Test cases are never removed. Returns the number of removed inputs and answer sets
+    pub fn clear_cache(
+        &self,
+        year: Option<i32>,
+        day: Option<u8>,
+        answers: bool,
+    ) -> Result<usize, AocError> {
+        self.storage().clear(&self.profile, year, day, answers)
+    }
+
+    pub(super) fn list_tables(
+        &self,
+        profile: &str,
+        year: Option<i32>,
+    ) -> Result<Vec<AocCacheEntry>, AocError> {
+        let conn = self.get_conn()?;
+        let map_err = |error| {
+            AocError::Database(DatabaseError::DatabaseQuerying {
+                object: "Cache".to_string(),
+                source: error,
+            })
+        };
+
+        let mut statement = conn
+            .prepare(
+                "SELECT d.year, d.day,
+                    EXISTS(SELECT 1 FROM Inputs i WHERE i.profile = ?1
+                        AND i.year = d.year AND i.day = d.day AND i.input IS NOT NULL),
+                    EXISTS(SELECT 1 FROM Inputs i WHERE i.profile = ?2
+                        AND i.year = d.year AND i.day = d.day AND i.test_input IS NOT NULL),
+                    EXISTS(SELECT 1 FROM Results r WHERE r.profile = ?1
+                        AND r.year = d.year AND r.day = d.day AND r.part_1 IS NOT NULL),
+                    EXISTS(SELECT 1 FROM Results r WHERE r.profile = ?1
+                        AND r.year = d.year AND r.day = d.day AND r.part_2 IS NOT NULL)
+                 FROM (
+                    SELECT year, day FROM Inputs
+                    WHERE profile = ?1 OR (profile = ?2 AND test_input IS NOT NULL)
+                    UNION
+                    SELECT year, day FROM Results WHERE profile = ?1
+                 ) d
+                 WHERE ?3 IS NULL OR d.year = ?3
+                 ORDER BY d.year, d.day",
+            )
+            .map_err(map_err)?;
+        let entries = statement
+            .query_map(params![profile, DEFAULT_PROFILE, year], |row| {
+                Ok(AocCacheEntry {
+                    year: row.get(0)?,
+                    day: row.get(1)?,
+                    input: row.get(2)?,
+                    test_input: row.get(3)?,
+                    part_1: row.get(4)?,
+                    part_2: row.get(5)?,
+                })
+            })
+            .map_err(map_err)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(map_err)?;
+
+        Ok(entries)
+    }
+
+    pub(super) fn clear_tables(
+        &self,
+        profile: &str,
+        year: Option<i32>,
+        day: Option<u8>,
+        answers: bool,
+    ) -> Result<usize, AocError> {
+        let mut cleared = self.execute(
+            "UPDATE Inputs SET input = NULL, input_hash = NULL, fetched_at = NULL
+             WHERE profile = ?1 AND input IS NOT NULL
+             AND (?2 IS NULL OR year = ?2) AND (?3 IS NULL OR day = ?3)",
+            params![profile, year, day],
+        )?;
+        if answers {
+            cleared += self.execute(
+                "DELETE FROM Results
+                 WHERE profile = ?1 AND (?2 IS NULL OR year = ?2) AND (?3 IS NULL OR day = ?3)",
+                params![profile, year, day],
+            )?;
+        }
+        self.execute(
+            "DELETE FROM Inputs WHERE input IS NULL AND test_input IS NULL",
+            [],
+        )?;
+
+        Ok(cleared)
+    }
+
+    /// Copies the inputs and answers of all profiles into a portable archive
+    pub fn export_cache(&self) -> Result<AocCacheArchive, AocError> {
+        let conn = self.get_conn()?;
+        let map_err = |error| {
+            AocError::Database(DatabaseError::DatabaseQuerying {
+                object: "Cache".to_string(),
+                source: error,
+            })
+        };
+
+        let inputs = conn
+            .prepare(
+                "SELECT profile, year, day, input, test_input, input_hash, fetched_at FROM Inputs
+                 ORDER BY profile, year, day",
+            )
+            .map_err(map_err)?
+            .query_map([], |row| {
+                Ok(ArchivedInput {
+                    profile: row.get(0)?,
+                    year: row.get(1)?,
+                    day: row.get(2)?,
+                    input: row.get(3)?,
+                    test_input: row.get(4)?,
+                    input_hash: row.get(5)?,
+                    fetched_at: row.get(6)?,
+                })
+            })
+            .map_err(map_err)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(map_err)?;
+        let results = conn
+            .prepare("SELECT profile, year, day, part_1, part_2 FROM Results ORDER BY profile, year, day")
+            .map_err(map_err)?
+            .query_map([], archived_result)
+            .map_err(map_err)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(map_err)?;
+        let test_results = conn
+            .prepare("SELECT profile, year, day, part_1, part_2 FROM Test_Results ORDER BY profile, year, day")
+            .map_err(map_err)?
+            .query_map([], archived_result)
+            .map_err(map_err)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(map_err)?;
+
+        Ok(AocCacheArchive {
+            version: CACHE_ARCHIVE_VERSION,
+            inputs,
+            results,
+            test_results,
+        })
+    }
+
+    /// Merges an archive into the cache. Values in the archive replace the cached ones,
+    /// values missing in the archive are kept. Returns the number of imported rows
+    pub fn import_cache(&self, archive: &AocCacheArchive) -> Result<usize, AocError> {
+        if archive.version > CACHE_ARCHIVE_VERSION {
+            return Err(AocError::Database(DatabaseError::UnsupportedArchive {
+                version: archive.version,
+                supported: CACHE_ARCHIVE_VERSION,
+            }));
+        }
+
+        let mut conn = self.get_conn()?;
+        let map_err = |command: &str| {
+            let command = command.to_string();
+            move |error| {
+                AocError::Database(DatabaseError::DatabaseExec {
+                    command,
+                    source: error,
+                })
+            }
+        };
+        let transaction = conn.transaction().map_err(map_err("BEGIN TRANSACTION"))?;
+
+        let mut imported = 0;
+        let command = "INSERT INTO Inputs
+                       (profile, year, day, input, test_input, input_hash, fetched_at)
+                       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                       ON CONFLICT(profile, year, day) DO UPDATE SET
+                       input = COALESCE(excluded.input, input),
+                       test_input = COALESCE(excluded.test_input, test_input),
+                       input_hash = COALESCE(excluded.input_hash, input_hash),
+                       fetched_at = COALESCE(excluded.fetched_at, fetched_at)";
+        for row in archive.inputs.iter() {
+            // Archives written before hashes were recorded get them on import
+            let hash = match (&row.input_hash, &row.input) {
+                (None, Some(input)) => Some(input_hash(input)),
+                (hash, _) => hash.clone(),
+            };
+            imported += transaction
+                .execute(
+                    command,
+                    params![
+                        row.profile,
+                        row.year,
+                        row.day,
+                        row.input,
+                        row.test_input,
+                        hash,
+                        row.fetched_at
+                    ],
+                )
+                .map_err(map_err(command))?;
+        }
+
+        for (table, rows) in [
+            ("Results", &archive.results),
+            ("Test_Results", &archive.test_results),
+        ] {
+            let command = format!(
+                "INSERT INTO {table} (profile, year, day, part_1, part_2)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(profile, year, day) DO UPDATE SET
+                 part_1 = COALESCE(excluded.part_1, part_1),
+                 part_2 = COALESCE(excluded.part_2, part_2)"
+            );
+            for row in rows.iter() {
+                imported += transaction
+                    .execute(
+                        &command,
+                        params![row.profile, row.year, row.day, row.part_1, row.part_2],
+                    )
+                    .map_err(map_err(&command))?;
+            }
+        }
+
+        transaction.commit().map_err(map_err("COMMIT"))?;
+        Ok(imported)
+    }
+
+    /// Compares the cached inputs of the profile against their stored hashes.
+    /// With `rehash` unhashed and changed inputs get the hash of their current content
+    pub fn verify_inputs(
+        &self,
+        year: Option<i32>,
+        rehash: bool,
+    ) -> Result<Vec<AocInputCheck>, AocError> {
+        let rows = {
+            let conn = self.get_conn()?;
+            let map_err = |error| {
+                AocError::Database(DatabaseError::DatabaseQuerying {
+                    object: "Inputs".to_string(),
+                    source: error,
+                })
+            };
+            let mut statement = conn
+                .prepare(
+                    "SELECT year, day, input, input_hash, fetched_at FROM Inputs
+                     WHERE profile = ?1 AND input IS NOT NULL AND (?2 IS NULL OR year = ?2)
+                     ORDER BY year, day",
+                )
+                .map_err(map_err)?;
+            let rows = statement
+                .query_map(params![self.profile, year], |row| {
+                    Ok((
+                        row.get::<usize, i32>(0)?,
+                        row.get::<usize, u8>(1)?,
+                        row.get::<usize, String>(2)?,
+                        row.get::<usize, Option<String>>(3)?,
+                        row.get::<usize, Option<String>>(4)?,
+                    ))
+                })
+                .map_err(map_err)?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(map_err)?;
+            rows
+        };
+
+        let mut checks = Vec::with_capacity(rows.len());
+        for (year, day, input, hash, fetched_at) in rows {
+            let status = match hash {
+                Some(hash) if hash == input_hash(&input) => InputStatus::Valid,
+                _ if rehash => {
+                    self.execute(
+                        "UPDATE Inputs SET input_hash = ?4
+                         WHERE profile = ?1 AND year = ?2 AND day = ?3",
+                        params![self.profile, year, day, input_hash(&input)],
+                    )?;
+                    InputStatus::Valid
+                }
+                Some(_) => InputStatus::Mismatch,
+                None => InputStatus::Unhashed,
+            };
+            checks.push(AocInputCheck {
+                year,
+                day,
+                fetched_at,
+                status,
+            });
+        }
+
+        Ok(checks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statuses(db: &AocDatabase, rehash: bool) -> Vec<InputStatus> {
+        db.verify_inputs(Some(2023), rehash)
+            .unwrap()
+            .into_iter()
+            .map(|check| check.status)
+            .collect()
+    }
+
+    #[test]
+    fn verify_reports_tampered_inputs() {
+        let db = AocDatabase::in_memory().unwrap();
+        db.set_input(2023, 1, false, "input".to_string()).unwrap();
+        db.set_input(2023, 2, false, "input".to_string()).unwrap();
+        assert_eq!(
+            statuses(&db, false),
+            [InputStatus::Valid, InputStatus::Valid]
+        );
+
+        db.execute(
+            "UPDATE Inputs SET input = 'edited' WHERE year = 2023 AND day = 2",
+            [],
+        )
+        .unwrap();
+        assert_eq!(
+            statuses(&db, false),
+            [InputStatus::Valid, InputStatus::Mismatch]
+        );
+
+        assert_eq!(
+            statuses(&db, true),
+            [InputStatus::Valid, InputStatus::Valid]
+        );
+        assert_eq!(
+            statuses(&db, false),
+            [InputStatus::Valid, InputStatus::Valid]
+        );
+    }
+
+    #[test]
+    fn rehash_stores_missing_hashes() {
+        let db = AocDatabase::in_memory().unwrap();
+        db.set_input(2023, 1, false, "input".to_string()).unwrap();
+        db.execute("UPDATE Inputs SET input_hash = NULL", [])
+            .unwrap();
+
+        assert_eq!(statuses(&db, false), [InputStatus::Unhashed]);
+        assert_eq!(statuses(&db, true), [InputStatus::Valid]);
+        assert_eq!(statuses(&db, false), [InputStatus::Valid]);
+    }
+}
@@ -0,0 +1,72 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, OptionalExtension};
+
+use crate::error::{AocError, DatabaseError};
+
+use super::AocDatabase;
+
+impl AocDatabase {
+    pub(super) fn create_leaderboards(&self) -> Result<(), AocError> {
+        self.execute(
+            "CREATE TABLE IF NOT EXISTS Leaderboards (
+                profile TEXT NOT NULL,
+                year INTEGER NOT NULL,
+                id INTEGER NOT NULL,
+                fetched_at TEXT NOT NULL,
+                body TEXT NOT NULL,
+                PRIMARY KEY(profile, year, id)
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// Gets the cached JSON of a private leaderboard and when it was fetched
+    pub fn get_leaderboard(
+        &self,
+        year: i32,
+        id: u64,
+    ) -> Result<Option<(DateTime<Utc>, String)>, AocError> {
+        let conn = self.get_conn()?;
+        let row = conn
+            .query_row(
+                "SELECT fetched_at, body FROM Leaderboards
+                 WHERE profile = ?1 AND year = ?2 AND id = ?3",
+                params![self.profile, year, id],
+                |row| Ok((row.get::<usize, String>(0)?, row.get::<usize, String>(1)?)),
+            )
+            .optional()
+            .map_err(|error| {
+                AocError::Database(DatabaseError::DatabaseQuerying {
+                    object: "Leaderboard".to_string(),
+                    source: error,
+                })
+            })?;
+
+        Ok(row.and_then(|(fetched_at, body)| {
+            let fetched_at = DateTime::parse_from_rfc3339(&fetched_at).ok()?;
+            Some((fetched_at.with_timezone(&Utc), body))
+        }))
+    }
+
+    /// Caches the JSON of a private leaderboard as fetched now
+    pub fn set_leaderboard(&self, year: i32, id: u64, body: &str) -> Result<(), AocError> {
+        self.execute(
+            "INSERT INTO Leaderboards (profile, year, id, fetched_at, body)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(profile, year, id) DO UPDATE SET
+             fetched_at = excluded.fetched_at,
+             body = excluded.body",
+            params![
+                self.profile,
+                year,
+                id,
+                Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+                body
+            ],
+        )?;
+
+        Ok(())
+    }
+}
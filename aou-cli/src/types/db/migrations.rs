@@ -0,0 +1,311 @@
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+
+use crate::error::{AocError, DatabaseError};
+
+use super::{session::restrict_permissions, AocDatabase};
+
+/// A step upgrading the schema by one version
+#[derive(Clone, Copy)]
+pub(super) struct Migration {
+    description: &'static str,
+    up: fn(&AocDatabase) -> Result<(), AocError>,
+}
+
+/// All steps in order. After the step at index `i` ran, the database has version `i + 1`.
+/// Only ever append steps, released ones must not change.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "Create the tables, upgrading databases created before schema versioning",
+        up: initial_schema,
+    },
+    Migration {
+        description: "Cache private leaderboards",
+        up: AocDatabase::create_leaderboards,
+    },
+];
+
+/// The tables as they were before the schema got a version. Every step only changes what is
+/// missing, so it also brings unversioned databases of any older release up to date
+fn initial_schema(db: &AocDatabase) -> Result<(), AocError> {
+    db.create_inputs()?;
+    db.create_results()?;
+    db.create_test_results()?;
+    db.create_benchmarks()?;
+    db.create_session()?;
+    db.create_requests()?;
+    db.create_input_options()?;
+    Ok(())
+}
+
+impl AocDatabase {
+    pub(super) fn user_version(&self) -> Result<u32, AocError> {
+        let conn = self.get_conn()?;
+        conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|error| {
+                AocError::Database(DatabaseError::DatabaseQuerying {
+                    object: "Schema version".to_string(),
+                    source: error,
+                })
+            })
+    }
+
+    fn has_tables(&self) -> Result<bool, AocError> {
+        let conn = self.get_conn()?;
+        conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'",
+            [],
+            |row| row.get::<usize, i32>(0),
+        )
+        .map(|count| count > 0)
+        .map_err(|error| {
+            AocError::Database(DatabaseError::DatabaseQuerying {
+                object: "Tables".to_string(),
+                source: error,
+            })
+        })
+    }
+
+    /// Copies the database next to itself before it gets migrated
+    fn backup(&self, path: &Path, version: u32) -> Result<PathBuf, AocError> {
+        let name = format!(
+            "{}.v{}-{}.bak",
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("aou.db3"),
+            version,
+            Utc::now().format("%Y%m%d%H%M%S")
+        );
+        let backup = path.with_file_name(name);
+
+        let conn = self.get_conn()?;
+        conn.execute("VACUUM INTO ?1", [backup.to_string_lossy().to_string()])
+            .map_err(|error| {
+                AocError::Database(DatabaseError::BackupFailed {
+                    path: backup.clone(),
+                    source: error,
+                })
+            })?;
+        // The backup contains the session tokens as well
+        restrict_permissions(&backup)?;
+
+        Ok(backup)
+    }
+
+    /// Runs all migration steps the database has not seen yet
+    pub(super) fn migrate(&self) -> Result<(), AocError> {
+        self.migrate_with(MIGRATIONS)
+    }
+
+    fn migrate_with(&self, migrations: &[Migration]) -> Result<(), AocError> {
+        let version = self.user_version()?;
+        let target = migrations.len() as u32;
+        if version > target {
+            return Err(AocError::Database(DatabaseError::SchemaTooNew {
+                version,
+                supported: target,
+            }));
+        }
+        if version == target {
+            return Ok(());
+        }
+
+        let backup = match &self.path {
+            Some(path) if self.has_tables()? => Some(self.backup(path, version)?),
+            _ => None,
+        };
+
+        for (index, migration) in migrations.iter().enumerate().skip(version as usize) {
+            let step = index as u32 + 1;
+            (migration.up)(self).map_err(|error| {
+                AocError::Database(DatabaseError::MigrationFailed {
+                    version: step,
+                    description: migration.description.to_string(),
+                    backup: backup.clone(),
+                    source: Box::new(error),
+                })
+            })?;
+            self.execute_batch(&format!("PRAGMA user_version = {step}"))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{types::DEFAULT_PROFILE, Parts};
+    use rusqlite::Connection;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Version of the schema this build works with
+    const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+    /// Files in the directory which are backups of the database
+    fn backups(dir: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "bak"))
+            .collect()
+    }
+
+    #[test]
+    fn new_database_gets_current_version_without_backup() {
+        let dir = TempDir::new().unwrap();
+        let db = AocDatabase::open(&dir.path().join("aou.db3")).unwrap();
+
+        assert_eq!(db.user_version().unwrap(), SCHEMA_VERSION);
+        assert!(backups(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn upgrades_unversioned_database() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("aou.db3");
+
+        // The schema as it was written before versioning and profiles existed
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE Inputs (
+                year INTEGER NOT NULL,
+                day INTEGER NOT NULL,
+                input TEXT,
+                test_input TEXT,
+                PRIMARY KEY(year, day)
+             );
+             CREATE TABLE Results (
+                year INTEGER NOT NULL,
+                day INTEGER NOT NULL,
+                part_1 TEXT,
+                part_2 TEXT,
+                PRIMARY KEY(year, day)
+             );
+             CREATE TABLE Test_Results (
+                year INTEGER NOT NULL,
+                day INTEGER NOT NULL,
+                part_1 TEXT,
+                part_2 TEXT,
+                PRIMARY KEY(year, day)
+             );
+             INSERT INTO Inputs VALUES (2023, 1, 'input', 'test input');
+             INSERT INTO Results VALUES (2023, 1, '42', NULL);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let db = AocDatabase::open(&path).unwrap();
+
+        assert_eq!(db.user_version().unwrap(), SCHEMA_VERSION);
+        assert_eq!(db.profile(), DEFAULT_PROFILE);
+        assert_eq!(db.get_input(2023, 1, false).unwrap(), "input");
+        assert_eq!(db.get_input(2023, 1, true).unwrap(), "test input");
+        assert_eq!(db.get_results(2023, 1, Parts::Part1, false).unwrap(), "42");
+        assert!(!db.has_result(2023, 1, Parts::Part2, false).unwrap());
+
+        // The backup still has the old schema
+        let backups = backups(dir.path());
+        assert_eq!(backups.len(), 1);
+        let backup = Connection::open(&backups[0]).unwrap();
+        let version: u32 = backup
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 0);
+        let input: String = backup
+            .query_row(
+                "SELECT input FROM Inputs WHERE year = 2023 AND day = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(input, "input");
+    }
+
+    #[test]
+    fn reopening_current_database_does_not_migrate() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("aou.db3");
+        let db = AocDatabase::open(&path).unwrap();
+        db.set_input(2023, 1, false, "input".to_string()).unwrap();
+        drop(db);
+
+        let db = AocDatabase::open(&path).unwrap();
+
+        assert_eq!(db.get_input(2023, 1, false).unwrap(), "input");
+        assert!(backups(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn runs_pending_steps_in_order() {
+        let dir = TempDir::new().unwrap();
+        let db = AocDatabase::open(&dir.path().join("aou.db3")).unwrap();
+        let migrations = [
+            MIGRATIONS,
+            &[
+                Migration {
+                    description: "Add a table",
+                    up: |db| db.execute_batch("CREATE TABLE Steps (step INTEGER NOT NULL)"),
+                },
+                Migration {
+                    description: "Fill the table",
+                    up: |db| db.execute_batch("INSERT INTO Steps VALUES (3)"),
+                },
+            ],
+        ]
+        .concat();
+
+        db.migrate_with(&migrations).unwrap();
+
+        assert_eq!(db.user_version().unwrap(), SCHEMA_VERSION + 2);
+        let step: u32 = db
+            .get_conn()
+            .unwrap()
+            .query_row("SELECT step FROM Steps", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(step, 3);
+        assert_eq!(backups(dir.path()).len(), 1);
+    }
+
+    #[test]
+    fn failed_step_keeps_version_and_names_backup() {
+        let dir = TempDir::new().unwrap();
+        let db = AocDatabase::open(&dir.path().join("aou.db3")).unwrap();
+        let migrations = [
+            MIGRATIONS,
+            &[Migration {
+                description: "Broken step",
+                up: |db| db.execute_batch("ALTER TABLE Missing ADD COLUMN value TEXT"),
+            }],
+        ]
+        .concat();
+
+        let error = db.migrate_with(&migrations).unwrap_err();
+
+        assert!(matches!(
+            error,
+            AocError::Database(DatabaseError::MigrationFailed {
+                version,
+                backup: Some(_),
+                ..
+            }) if version == SCHEMA_VERSION + 1
+        ));
+        assert_eq!(db.user_version().unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn refuses_newer_schema() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("aou.db3");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(&format!("PRAGMA user_version = {}", SCHEMA_VERSION + 1))
+            .unwrap();
+        drop(conn);
+
+        assert!(matches!(
+            AocDatabase::open(&path),
+            Err(AocError::Database(DatabaseError::SchemaTooNew { .. }))
+        ));
+    }
+}
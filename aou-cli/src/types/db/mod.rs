@@ -1,12 +1,28 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
 use directories::ProjectDirs;
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::Params;
+use rusqlite::{OpenFlags, Params};
 
 use crate::error::{AocError, DatabaseError};
 
+use super::AocStorage;
+
+mod benchmarks;
+mod cache;
+mod leaderboards;
+mod migrations;
+mod normalization;
+mod requests;
+mod session;
 mod tables;
 
 fn get_data_path() -> Result<PathBuf, AocError> {
@@ -17,56 +33,155 @@ fn get_data_path() -> Result<PathBuf, AocError> {
     }
 }
 
+/// Shown instead of a path for in-memory databases
+const MEMORY_PATH: &str = ":memory:";
+
+/// Profile used when no `--profile` is given. Test inputs and results are always stored under it
+pub const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Clone)]
 pub struct AocDatabase {
     pool: Pool<SqliteConnectionManager>,
+    /// `None` for in-memory databases
+    path: Option<PathBuf>,
+    profile: String,
+    /// Backend of the inputs and answers. The SQLite tables are used if not set
+    storage: Option<Arc<dyn AocStorage>>,
 }
 
 impl AocDatabase {
+    /// Opens the database in the local data directory of the user
     pub fn new() -> Result<Self, AocError> {
-        let path = get_data_path()?.join("aou.db3");
+        Self::in_dir(&get_data_path()?)
+    }
 
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).map_err(|error| {
-                AocError::Database(DatabaseError::DirectoryCreateFailed {
-                    path: parent.to_path_buf(),
-                    source: error,
-                })
-            })?;
-        }
+    /// Opens the database in the given directory
+    pub fn in_dir(dir: &Path) -> Result<Self, AocError> {
+        fs::create_dir_all(dir).map_err(|error| {
+            AocError::Database(DatabaseError::DirectoryCreateFailed {
+                path: dir.to_path_buf(),
+                source: error,
+            })
+        })?;
+        Self::open(&dir.join("aou.db3"))
+    }
 
-        let manager = SqliteConnectionManager::file(&path);
-        let pool = Pool::builder()
+    /// Opens the database file at the path and migrates it to the current schema
+    pub fn open(path: &Path) -> Result<Self, AocError> {
+        let manager = SqliteConnectionManager::file(path);
+        Self::from_manager(manager, Some(path.to_path_buf()), Pool::builder())
+    }
+
+    /// Creates an empty database which only lives as long as the returned value and its clones
+    pub fn in_memory() -> Result<Self, AocError> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        // All connections of the pool have to share the same in-memory database
+        let uri = format!(
+            "file:aou-memory-{}-{}?mode=memory&cache=shared",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let manager = SqliteConnectionManager::file(uri).with_flags(
+            OpenFlags::SQLITE_OPEN_READ_WRITE
+                | OpenFlags::SQLITE_OPEN_CREATE
+                | OpenFlags::SQLITE_OPEN_URI,
+        );
+        // The database is gone once its last connection closes
+        let builder = Pool::builder().max_lifetime(None).idle_timeout(None);
+        Self::from_manager(manager, None, builder)
+    }
+
+    fn from_manager(
+        manager: SqliteConnectionManager,
+        path: Option<PathBuf>,
+        builder: r2d2::Builder<SqliteConnectionManager>,
+    ) -> Result<Self, AocError> {
+        let manager = manager.with_init(|conn| conn.execute_batch("PRAGMA foreign_keys = ON"));
+        let pool = builder
             .max_size(10) // Adjust pool size as needed
             .build(manager)
             .map_err(|error| {
                 AocError::Database(DatabaseError::ConnectionFailed {
-                    path: path.clone(),
+                    path: path.clone().unwrap_or(MEMORY_PATH.into()),
                     source: error,
                 })
             })?;
 
-        // Initialize database with first connection
-        let conn = pool.get().map_err(|error| {
-            AocError::Database(DatabaseError::ConnectionFailed {
-                path: path.clone(),
-                source: error,
-            })
-        })?;
+        let db = Self {
+            pool,
+            path,
+            profile: DEFAULT_PROFILE.to_string(),
+            storage: None,
+        };
+        db.migrate()?;
 
-        if let Err(error) = conn.execute("PRAGMA foreign_keys = ON", []) {
-            return Err(AocError::Database(DatabaseError::DatabaseExec {
-                command: "PRAGMA foreign_keys = ON".to_string(),
-                source: error,
-            }));
+        Ok(db)
+    }
+
+    /// Keeps the inputs and answers in the given backend instead of the SQLite tables.
+    /// Session tokens, benchmarks and the request log stay in the database
+    pub fn with_storage(mut self, storage: Arc<dyn AocStorage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Backend of the inputs and answers
+    fn storage(&self) -> &dyn AocStorage {
+        match &self.storage {
+            Some(storage) => storage.as_ref(),
+            None => self,
         }
+    }
 
-        let db = Self { pool };
+    /// Path used in error messages
+    fn display_path(&self) -> PathBuf {
+        self.path.clone().unwrap_or(MEMORY_PATH.into())
+    }
 
-        db.create_inputs()?;
-        db.create_results()?;
-        db.create_test_results()?;
+    /// Uses the inputs, results and session token of the given profile
+    pub fn with_profile(mut self, profile: &str) -> Self {
+        self.profile = profile.to_string();
+        self
+    }
 
-        Ok(db)
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// Profile under which the inputs and results are stored
+    fn storage_profile(&self, test: bool) -> &str {
+        match test {
+            true => DEFAULT_PROFILE,
+            false => &self.profile,
+        }
+    }
+
+    pub fn execute_batch(&self, sql: &str) -> Result<(), AocError> {
+        let conn = self.get_conn()?;
+
+        conn.execute_batch(sql).map_err(|error| {
+            AocError::Database(DatabaseError::DatabaseExec {
+                command: sql.to_string(),
+                source: error,
+            })
+        })
+    }
+
+    /// Checks if a table exists and has the given column
+    fn has_column(&self, table: &str, column: &str) -> Result<bool, AocError> {
+        let conn = self.get_conn()?;
+        match conn.query_row(
+            &format!("SELECT COUNT(*) FROM pragma_table_info('{table}') WHERE name = ?1"),
+            [column],
+            |row| row.get::<usize, i32>(0),
+        ) {
+            Ok(count) => Ok(count > 0),
+            Err(error) => Err(AocError::Database(DatabaseError::DatabaseQuerying {
+                object: format!("Columns of {table}"),
+                source: error,
+            })),
+        }
     }
 
     pub fn execute<P>(&self, sql: &str, params: P) -> Result<usize, AocError>
@@ -75,7 +190,7 @@ impl AocDatabase {
     {
         let conn = self.pool.get().map_err(|error| {
             AocError::Database(DatabaseError::ConnectionFailed {
-                path: get_data_path().unwrap().join("aou.db3"),
+                path: self.display_path(),
                 source: error,
             })
         })?;
@@ -93,9 +208,280 @@ impl AocDatabase {
     pub fn get_conn(&self) -> Result<r2d2::PooledConnection<SqliteConnectionManager>, AocError> {
         self.pool.get().map_err(|error| {
             AocError::Database(DatabaseError::ConnectionFailed {
-                path: get_data_path().unwrap().join("aou.db3"),
+                path: self.display_path(),
                 source: error,
             })
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::{AocCacheEntry, AocResult, AocYear},
+        Parts,
+    };
+    use advent_of_utils::AocOption;
+    use std::time::Duration;
+    use std::{collections::HashMap, sync::Mutex};
+
+    /// Profile, year, day, the part or `None` for the input, and whether it is a test case
+    type MapKey = (String, i32, u8, Option<Parts>, bool);
+
+    /// Keeps everything in a map to check that the database routes through the backend
+    #[derive(Default)]
+    struct MapStorage {
+        values: Mutex<HashMap<MapKey, String>>,
+    }
+
+    impl MapStorage {
+        fn load(&self, key: MapKey) -> Result<Option<String>, AocError> {
+            Ok(self.values.lock().unwrap().get(&key).cloned())
+        }
+
+        fn store(&self, key: MapKey, value: &str) -> Result<(), AocError> {
+            self.values.lock().unwrap().insert(key, value.to_string());
+            Ok(())
+        }
+    }
+
+    impl AocStorage for MapStorage {
+        fn load_input(
+            &self,
+            profile: &str,
+            year: i32,
+            day: u8,
+            test: bool,
+        ) -> Result<Option<String>, AocError> {
+            self.load((profile.to_string(), year, day, None, test))
+        }
+
+        fn store_input(
+            &self,
+            profile: &str,
+            year: i32,
+            day: u8,
+            test: bool,
+            input: &str,
+        ) -> Result<(), AocError> {
+            self.store((profile.to_string(), year, day, None, test), input)
+        }
+
+        fn load_result(
+            &self,
+            profile: &str,
+            year: i32,
+            day: u8,
+            part: Parts,
+            test: bool,
+        ) -> Result<Option<String>, AocError> {
+            self.load((profile.to_string(), year, day, Some(part), test))
+        }
+
+        fn store_result(
+            &self,
+            profile: &str,
+            year: i32,
+            day: u8,
+            part: Parts,
+            test: bool,
+            result: &str,
+        ) -> Result<(), AocError> {
+            self.store((profile.to_string(), year, day, Some(part), test), result)
+        }
+
+        fn list(&self, profile: &str, year: Option<i32>) -> Result<Vec<AocCacheEntry>, AocError> {
+            let mut entries: Vec<AocCacheEntry> = Vec::new();
+            let values = self.values.lock().unwrap();
+            for (key_profile, key_year, day, part, test) in values.keys() {
+                if key_profile != profile || year.is_some_and(|year| year != *key_year) {
+                    continue;
+                }
+                let index = match entries
+                    .iter()
+                    .position(|entry| (entry.year, entry.day) == (*key_year, *day))
+                {
+                    Some(index) => index,
+                    None => {
+                        entries.push(AocCacheEntry {
+                            year: *key_year,
+                            day: *day,
+                            input: false,
+                            test_input: false,
+                            part_1: false,
+                            part_2: false,
+                        });
+                        entries.len() - 1
+                    }
+                };
+                let entry = &mut entries[index];
+                match (part, test) {
+                    (None, false) => entry.input = true,
+                    (None, true) => entry.test_input = true,
+                    (Some(Parts::Part1), false) => entry.part_1 = true,
+                    (Some(Parts::Part2), false) => entry.part_2 = true,
+                    (Some(_), true) => {}
+                }
+            }
+            entries.sort_by_key(|entry| (entry.year, entry.day));
+            Ok(entries)
+        }
+
+        fn clear(
+            &self,
+            profile: &str,
+            year: Option<i32>,
+            day: Option<u8>,
+            answers: bool,
+        ) -> Result<usize, AocError> {
+            let mut values = self.values.lock().unwrap();
+            let before = values.len();
+            values.retain(|(key_profile, key_year, key_day, part, test), _| {
+                key_profile != profile
+                    || *test
+                    || (part.is_some() && !answers)
+                    || year.is_some_and(|year| year != *key_year)
+                    || day.is_some_and(|day| day != *key_day)
+            });
+            Ok(before - values.len())
+        }
+    }
+
+    #[test]
+    fn in_memory_databases_are_separate() {
+        let first = AocDatabase::in_memory().unwrap();
+        let second = AocDatabase::in_memory().unwrap();
+
+        first
+            .set_input(2023, 1, false, "input".to_string())
+            .unwrap();
+
+        assert_eq!(first.get_input(2023, 1, false).unwrap(), "input");
+        assert!(!second.has_input(2023, 1, false).unwrap());
+    }
+
+    #[test]
+    fn profiles_share_only_test_data() {
+        let db = AocDatabase::in_memory().unwrap();
+        db.set_input(2023, 1, false, "default".to_string()).unwrap();
+        db.set_input(2023, 1, true, "test".to_string()).unwrap();
+        db.set_result(2023, 1, false, Parts::Part1, "1".to_string())
+            .unwrap();
+
+        let bob = db.clone().with_profile("bob");
+
+        assert!(!bob.has_input(2023, 1, false).unwrap());
+        assert_eq!(bob.get_input(2023, 1, true).unwrap(), "test");
+        assert!(!bob.has_result(2023, 1, Parts::Part1, false).unwrap());
+        assert!(db.get_input(2023, 2, false).is_err());
+    }
+
+    #[test]
+    fn storage_backend_replaces_tables() {
+        let db = AocDatabase::in_memory().unwrap();
+        let routed = db.clone().with_storage(Arc::new(MapStorage::default()));
+
+        routed
+            .set_input(2023, 1, false, "input".to_string())
+            .unwrap();
+        routed
+            .set_result(2023, 1, false, Parts::Part2, "2".to_string())
+            .unwrap();
+
+        assert_eq!(routed.get_input(2023, 1, false).unwrap(), "input");
+        assert_eq!(
+            routed.get_results(2023, 1, Parts::Part2, false).unwrap(),
+            "2"
+        );
+        assert!(!db.has_input(2023, 1, false).unwrap());
+        assert!(!db.has_result(2023, 1, Parts::Part2, false).unwrap());
+
+        let listed = routed.list_cache(Some(2023)).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].input && listed[0].part_2 && !listed[0].part_1);
+        assert!(db.list_cache(None).unwrap().is_empty());
+
+        assert_eq!(routed.clear_cache(None, None, false).unwrap(), 1);
+        assert!(!routed.has_input(2023, 1, false).unwrap());
+        assert!(routed.has_result(2023, 1, Parts::Part2, false).unwrap());
+    }
+
+    #[test]
+    fn free_star_needs_all_other_stars() {
+        let db = AocDatabase::in_memory().unwrap();
+        for day in 1..=12 {
+            db.set_result(2025, day, false, Parts::Part1, day.to_string())
+                .unwrap();
+        }
+        for day in 1..=10 {
+            db.set_result(2025, day, false, Parts::Part2, day.to_string())
+                .unwrap();
+        }
+        assert_eq!(db.stars(2025).unwrap().len(), 22);
+        assert!(!db.stars(2025).unwrap().contains(&(12, Parts::Part2)));
+
+        db.set_result(2025, 11, false, Parts::Part2, "11".to_string())
+            .unwrap();
+        let stars = db.stars(2025).unwrap();
+        assert_eq!(stars.len(), 24);
+        assert!(stars.contains(&(12, Parts::Part2)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn session_journals_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("aou.db3");
+        let wal = dir.path().join("aou.db3-wal");
+        let db = AocDatabase::open(&path).unwrap();
+        std::fs::write(&wal, "").unwrap();
+        std::fs::set_permissions(&wal, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        db.set_session("token").unwrap();
+
+        for file in [path, wal] {
+            let mode = std::fs::metadata(file).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn baselines_belong_to_their_profile() {
+        let db = AocDatabase::in_memory().unwrap();
+        let bob = db.clone().with_profile("bob");
+        let run = |ms| {
+            AocYear::from_vec(
+                2023,
+                vec![AocResult::new(
+                    1,
+                    1,
+                    AocOption::Int(1),
+                    vec![Duration::from_millis(ms)],
+                )],
+            )
+        };
+
+        db.add_benchmark(2023, None, "hash", Some("before"), &run(1))
+            .unwrap();
+        bob.add_benchmark(2023, None, "hash", Some("before"), &run(2))
+            .unwrap();
+
+        let time = |db: &AocDatabase| {
+            db.get_baseline_benchmark(2023, 1, Parts::Part1, "before")
+                .unwrap()
+                .unwrap()
+                .duration()
+                .clone()
+        };
+        assert_eq!(time(&db), vec![Duration::from_millis(1)]);
+        assert_eq!(time(&bob), vec![Duration::from_millis(2)]);
+        assert!(!db
+            .clone()
+            .with_profile("eve")
+            .has_baseline(2023, "before")
+            .unwrap());
+    }
+}
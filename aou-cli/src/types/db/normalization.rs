@@ -0,0 +1,66 @@
+use rusqlite::{params, OptionalExtension};
+
+use crate::{
+    error::{AocError, DatabaseError},
+    types::InputNormalization,
+};
+
+use super::AocDatabase;
+
+impl AocDatabase {
+    pub(super) fn create_input_options(&self) -> Result<(), AocError> {
+        self.execute(
+            "CREATE TABLE IF NOT EXISTS Input_Options (
+                year INTEGER NOT NULL,
+                day INTEGER NOT NULL,
+                trim_newline INTEGER NOT NULL DEFAULT 0,
+                normalize_crlf INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY(year, day)
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// Gets how the input of a day is normalized before it is handed to the solution
+    pub fn get_normalization(&self, year: i32, day: u8) -> Result<InputNormalization, AocError> {
+        let conn = self.get_conn()?;
+        conn.query_row(
+            "SELECT trim_newline, normalize_crlf FROM Input_Options
+             WHERE year = ?1 AND day = ?2",
+            params![year, day],
+            |row| Ok(InputNormalization::new(row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map(Option::unwrap_or_default)
+        .map_err(|error| {
+            AocError::Database(DatabaseError::DatabaseQuerying {
+                object: "Input options".to_string(),
+                source: error,
+            })
+        })
+    }
+
+    pub fn set_normalization(
+        &self,
+        year: i32,
+        day: u8,
+        normalization: InputNormalization,
+    ) -> Result<(), AocError> {
+        self.execute(
+            "INSERT INTO Input_Options (year, day, trim_newline, normalize_crlf)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(year, day) DO UPDATE SET
+             trim_newline = ?3, normalize_crlf = ?4",
+            params![
+                year,
+                day,
+                normalization.trim_newline,
+                normalization.normalize_crlf
+            ],
+        )?;
+
+        Ok(())
+    }
+}
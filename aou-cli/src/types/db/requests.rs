@@ -0,0 +1,106 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, OptionalExtension};
+
+use crate::{
+    error::{AocError, DatabaseError},
+    types::AocRequest,
+};
+
+use super::AocDatabase;
+
+impl AocDatabase {
+    pub(super) fn create_requests(&self) -> Result<(), AocError> {
+        self.execute(
+            "CREATE TABLE IF NOT EXISTS Requests (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL,
+                method TEXT NOT NULL,
+                url TEXT NOT NULL,
+                status INTEGER,
+                error TEXT
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// Adds a request made to Advent of Code to the request log
+    pub fn log_request(
+        &self,
+        method: &str,
+        url: &str,
+        status: Option<u16>,
+        error: Option<&str>,
+    ) -> Result<(), AocError> {
+        self.execute(
+            "INSERT INTO Requests (timestamp, method, url, status, error)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+                method,
+                url,
+                status,
+                error
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Gets the time of the latest request made by any invocation
+    pub fn last_request_time(&self) -> Result<Option<DateTime<Utc>>, AocError> {
+        let conn = self.get_conn()?;
+        let timestamp = conn
+            .query_row(
+                "SELECT timestamp FROM Requests ORDER BY id DESC LIMIT 1",
+                [],
+                |row| row.get::<usize, String>(0),
+            )
+            .optional()
+            .map_err(|error| {
+                AocError::Database(DatabaseError::DatabaseQuerying {
+                    object: "Request".to_string(),
+                    source: error,
+                })
+            })?;
+
+        Ok(timestamp
+            .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+            .map(|t| t.with_timezone(&Utc)))
+    }
+
+    /// Gets the latest requests, newest first
+    pub fn get_requests(&self, limit: usize) -> Result<Vec<AocRequest>, AocError> {
+        let conn = self.get_conn()?;
+        let map_err = |error| {
+            AocError::Database(DatabaseError::DatabaseQuerying {
+                object: "Requests".to_string(),
+                source: error,
+            })
+        };
+
+        let mut statement = conn
+            .prepare(
+                "SELECT timestamp, method, url, status, error FROM Requests
+                 ORDER BY id DESC
+                 LIMIT ?1",
+            )
+            .map_err(map_err)?;
+        let requests = statement
+            .query_map([limit], |row| {
+                Ok(AocRequest {
+                    timestamp: row.get(0)?,
+                    method: row.get(1)?,
+                    url: row.get(2)?,
+                    status: row.get(3)?,
+                    error: row.get(4)?,
+                })
+            })
+            .map_err(map_err)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(map_err)?;
+
+        Ok(requests)
+    }
+}
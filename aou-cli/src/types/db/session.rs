@@ -0,0 +1,98 @@
+use rusqlite::{params, OptionalExtension};
+use std::path::Path;
+
+use crate::error::{AocError, DatabaseError};
+
+use super::{AocDatabase, DEFAULT_PROFILE};
+
+impl AocDatabase {
+    pub(super) fn create_session(&self) -> Result<(), AocError> {
+        self.execute(
+            "CREATE TABLE IF NOT EXISTS Sessions (
+                profile TEXT PRIMARY KEY,
+                token TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // The single token stored before profiles existed belongs to the default profile
+        if self.has_column("Session", "token")? {
+            self.execute_batch(&format!(
+                "BEGIN;
+                 INSERT OR IGNORE INTO Sessions (profile, token)
+                 SELECT '{DEFAULT_PROFILE}', token FROM Session;
+                 DROP TABLE Session;
+                 COMMIT;"
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets the session token of the profile stored with `aou login`
+    pub fn get_session(&self) -> Result<Option<String>, AocError> {
+        let conn = self.get_conn()?;
+        conn.query_row(
+            "SELECT token FROM Sessions WHERE profile = ?1",
+            [&self.profile],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|error| {
+            AocError::Database(DatabaseError::DatabaseQuerying {
+                object: "Session".to_string(),
+                source: error,
+            })
+        })
+    }
+
+    /// Stores the session token and restricts the database to the current user
+    pub fn set_session(&self, token: &str) -> Result<(), AocError> {
+        self.execute(
+            "INSERT INTO Sessions (profile, token)
+             VALUES (?1, ?2)
+             ON CONFLICT(profile) DO UPDATE SET
+             token = ?2",
+            params![self.profile, token],
+        )?;
+        match &self.path {
+            Some(path) => restrict_permissions(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Removes the stored session token of the profile and returns whether one was stored
+    pub fn remove_session(&self) -> Result<bool, AocError> {
+        Ok(self.execute("DELETE FROM Sessions WHERE profile = ?1", [&self.profile])? > 0)
+    }
+}
+
+/// Makes a file containing session tokens and its SQLite journals readable only by the current user
+#[cfg(unix)]
+pub(super) fn restrict_permissions(path: &Path) -> Result<(), AocError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let journals = ["-journal", "-wal", "-shm"].map(|suffix| {
+        let mut journal = path.as_os_str().to_owned();
+        journal.push(suffix);
+        std::path::PathBuf::from(journal)
+    });
+
+    std::iter::once(path.to_path_buf())
+        .chain(journals.into_iter().filter(|journal| journal.exists()))
+        .try_for_each(|file| {
+            std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o600)).map_err(
+                |error| {
+                    AocError::Database(DatabaseError::PermissionsFailed {
+                        path: file.clone(),
+                        source: error,
+                    })
+                },
+            )
+        })
+}
+
+#[cfg(not(unix))]
+pub(super) fn restrict_permissions(_path: &Path) -> Result<(), AocError> {
+    Ok(())
+}
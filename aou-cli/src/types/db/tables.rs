@@ -1,12 +1,15 @@
-use rusqlite::params;
-use std::fmt::Display;
+use chrono::{SecondsFormat, Utc};
+use rusqlite::{params, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::{collections::HashSet, fmt::Display};
 
 use crate::{
-    error::{AocError, DatabaseError},
+    error::{AocError, DatabaseError, InputError},
+    types::{is_encrypted, AocCacheEntry, AocEvent, AocStorage},
     Parts,
 };
 
-use super::AocDatabase;
+use super::{AocDatabase, DEFAULT_PROFILE};
 
 #[derive(Debug, Clone, Copy)]
 enum Query {
@@ -31,100 +34,92 @@ impl Display for Table {
     }
 }
 
+/// Hex encoded SHA-256 hash of an input
+pub(super) fn input_hash(input: &str) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
 impl AocDatabase {
     fn buld_query(&self, table: Table, field: &str, query_type: Query) -> String {
         match query_type {
             Query::Insert => {
                 format!(
-                    "INSERT INTO {table} (year, day, {field})
-                     VALUES (?1, ?2, ?3)
-                     ON CONFLICT(year, day) DO UPDATE SET
-                     {field} = ?3"
+                    "INSERT INTO {table} (profile, year, day, {field})
+                     VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT(profile, year, day) DO UPDATE SET
+                     {field} = ?4"
                 )
             }
             Query::Select => {
                 format!(
                     "SELECT {field}
                      FROM {table}
-                     WHERE year = ?1 AND day = ?2",
+                     WHERE profile = ?1 AND year = ?2 AND day = ?3",
                 )
             }
         }
     }
 
+    /// Rebuilds a table created before profiles existed, keying the old rows to the default profile
+    fn add_profile_key(&self, table: Table, columns: &str) -> Result<(), AocError> {
+        if !self.has_column(&table.to_string(), "year")?
+            || self.has_column(&table.to_string(), "profile")?
+        {
+            return Ok(());
+        }
+
+        self.execute_batch(&format!(
+            "BEGIN;
+             ALTER TABLE {table} RENAME TO {table}_Old;
+             CREATE TABLE {table} (
+                profile TEXT NOT NULL,
+                year INTEGER NOT NULL,
+                day INTEGER NOT NULL,
+                {columns},
+                PRIMARY KEY(profile, year, day)
+             );
+             INSERT INTO {table} SELECT '{DEFAULT_PROFILE}', * FROM {table}_Old;
+             DROP TABLE {table}_Old;
+             COMMIT;"
+        ))
+    }
+
     pub(super) fn create_inputs(&self) -> Result<(), AocError> {
+        self.add_profile_key(Table::Inputs, "input TEXT, test_input TEXT")?;
         self.execute(
             "CREATE TABLE IF NOT EXISTS Inputs (
+                profile TEXT NOT NULL,
                 year INTEGER NOT NULL,
                 day INTEGER NOT NULL,
                 input TEXT,
                 test_input TEXT,
-                PRIMARY KEY(year, day)
+                PRIMARY KEY(profile, year, day)
             )",
             [],
         )?;
 
-        Ok(())
-    }
-
-    pub fn get_input(&self, year: i32, day: u8, test: bool) -> Result<String, AocError> {
-        let field = match test {
-            true => "test_input",
-            false => "input",
-        };
-        let query = self.buld_query(Table::Inputs, field, Query::Select);
-        let conn = self.get_conn()?;
-        let res = conn.query_row(&query, [year, day.into()], |row| row.get(0));
-        match res {
-            Ok(s) => Ok(s),
-            Err(error) => Err(AocError::Database(DatabaseError::DatabaseQuerying {
-                object: field.to_string(),
-                source: error,
-            })),
+        for column in ["input_hash", "fetched_at"] {
+            if !self.has_column("Inputs", column)? {
+                self.execute(&format!("ALTER TABLE Inputs ADD COLUMN {column} TEXT"), [])?;
+            }
         }
-    }
 
-    pub fn set_input(&self, year: i32, day: u8, test: bool, input: String) -> Result<(), AocError> {
-        let field = match test {
-            true => "test_input",
-            false => "input",
-        };
-        let query = self.buld_query(Table::Inputs, field, Query::Insert);
-        self.execute(&query, params![year, day, input])?;
         Ok(())
     }
 
-    pub fn has_input(&self, year: i32, day: u8, test: bool) -> Result<bool, AocError> {
-        let field = match test {
-            true => "test_input",
-            false => "input",
-        };
-        let conn = self.get_conn()?;
-        match conn.query_row(
-            &format!(
-                "SELECT COUNT({field}) FROM Inputs 
-                 WHERE year = ? AND day = ? 
-                 AND {field} IS NOT NULL"
-            ),
-            [year, day.into()],
-            |row| row.get::<usize, i32>(0),
-        ) {
-            Ok(count) => Ok(count > 0),
-            Err(error) => Err(AocError::Database(DatabaseError::DatabaseQuerying {
-                object: "Input".to_string(),
-                source: error,
-            })),
-        }
-    }
-
     pub(super) fn create_results(&self) -> Result<(), AocError> {
+        self.add_profile_key(Table::Results, "part_1 TEXT, part_2 TEXT")?;
         self.execute(
             "CREATE TABLE IF NOT EXISTS Results (
+                profile TEXT NOT NULL,
                 year INTEGER NOT NULL,
                 day INTEGER NOT NULL,
                 part_1 TEXT,
                 part_2 TEXT,
-                PRIMARY KEY(year, day)
+                PRIMARY KEY(profile, year, day)
             )",
             [],
         )?;
@@ -133,47 +128,198 @@ impl AocDatabase {
     }
 
     pub(super) fn create_test_results(&self) -> Result<(), AocError> {
+        self.add_profile_key(Table::TestResults, "part_1 TEXT, part_2 TEXT")?;
         self.execute(
             "CREATE TABLE IF NOT EXISTS Test_Results (
+                profile TEXT NOT NULL,
                 year INTEGER NOT NULL,
                 day INTEGER NOT NULL,
                 part_1 TEXT,
                 part_2 TEXT,
-                PRIMARY KEY(year, day)
+                PRIMARY KEY(profile, year, day)
             )",
             [],
         )?;
 
         Ok(())
     }
+}
+
+fn input_field(test: bool) -> &'static str {
+    match test {
+        true => "test_input",
+        false => "input",
+    }
+}
 
-    pub fn get_results(
+fn result_field(part: Parts) -> &'static str {
+    match part {
+        Parts::Part1 => "part_1",
+        Parts::Part2 => "part_2",
+    }
+}
+
+fn result_table(test: bool) -> Table {
+    match test {
+        true => Table::TestResults,
+        false => Table::Results,
+    }
+}
+
+/// Error for a value which is not stored
+fn missing(object: &str) -> AocError {
+    AocError::Database(DatabaseError::DatabaseQuerying {
+        object: object.to_string(),
+        source: rusqlite::Error::QueryReturnedNoRows,
+    })
+}
+
+impl AocStorage for AocDatabase {
+    fn load_input(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        test: bool,
+    ) -> Result<Option<String>, AocError> {
+        let field = input_field(test);
+        let query = self.buld_query(Table::Inputs, field, Query::Select);
+        let conn = self.get_conn()?;
+        conn.query_row(&query, params![profile, year, day], |row| {
+            row.get::<usize, Option<String>>(0)
+        })
+        .optional()
+        .map(Option::flatten)
+        .map_err(|error| {
+            AocError::Database(DatabaseError::DatabaseQuerying {
+                object: field.to_string(),
+                source: error,
+            })
+        })
+    }
+
+    /// Puzzle inputs are stored together with their hash and the current time
+    fn store_input(
         &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        test: bool,
+        input: &str,
+    ) -> Result<(), AocError> {
+        if test {
+            let query = self.buld_query(Table::Inputs, "test_input", Query::Insert);
+            self.execute(&query, params![profile, year, day, input])?;
+            return Ok(());
+        }
+
+        self.execute(
+            "INSERT INTO Inputs (profile, year, day, input, input_hash, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(profile, year, day) DO UPDATE SET
+             input = ?4, input_hash = ?5, fetched_at = ?6",
+            params![
+                profile,
+                year,
+                day,
+                input,
+                input_hash(input),
+                Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn load_result(
+        &self,
+        profile: &str,
         year: i32,
         day: u8,
         part: Parts,
         test: bool,
-    ) -> Result<String, AocError> {
-        let field = match part {
-            Parts::Part1 => "part_1",
-            Parts::Part2 => "part_2",
-        };
-        let table = match test {
-            true => Table::TestResults,
-            false => Table::Results,
-        };
-        let query = self.buld_query(table, field, Query::Select);
+    ) -> Result<Option<String>, AocError> {
+        let field = result_field(part);
+        let query = self.buld_query(result_table(test), field, Query::Select);
         let conn = self.get_conn()?;
-        let res = conn.query_row(&query, [year, day.into()], |row| row.get(0));
-        match res {
-            Ok(s) => Ok(s),
-            Err(error) => Err(AocError::Database(DatabaseError::DatabaseQuerying {
+        conn.query_row(&query, params![profile, year, day], |row| {
+            row.get::<usize, Option<String>>(0)
+        })
+        .optional()
+        .map(Option::flatten)
+        .map_err(|error| {
+            AocError::Database(DatabaseError::DatabaseQuerying {
                 object: field.to_string(),
                 source: error,
-            })),
+            })
+        })
+    }
+
+    fn store_result(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        part: Parts,
+        test: bool,
+        result: &str,
+    ) -> Result<(), AocError> {
+        let query = self.buld_query(result_table(test), result_field(part), Query::Insert);
+        self.execute(&query, params![profile, year, day, result])?;
+        Ok(())
+    }
+
+    fn list(&self, profile: &str, year: Option<i32>) -> Result<Vec<AocCacheEntry>, AocError> {
+        self.list_tables(profile, year)
+    }
+
+    fn clear(
+        &self,
+        profile: &str,
+        year: Option<i32>,
+        day: Option<u8>,
+        answers: bool,
+    ) -> Result<usize, AocError> {
+        self.clear_tables(profile, year, day, answers)
+    }
+}
+
+impl AocDatabase {
+    pub fn get_input(&self, year: i32, day: u8, test: bool) -> Result<String, AocError> {
+        let input = self
+            .storage()
+            .load_input(self.storage_profile(test), year, day, test)?
+            .ok_or_else(|| missing(input_field(test)))?;
+        // Only reaches here encrypted if no key was given
+        match is_encrypted(&input) {
+            true => Err(AocError::Input(InputError::Encrypted { year, day })),
+            false => Ok(input),
         }
     }
 
+    pub fn set_input(&self, year: i32, day: u8, test: bool, input: String) -> Result<(), AocError> {
+        self.storage()
+            .store_input(self.storage_profile(test), year, day, test, &input)
+    }
+
+    pub fn has_input(&self, year: i32, day: u8, test: bool) -> Result<bool, AocError> {
+        Ok(self
+            .storage()
+            .load_input(self.storage_profile(test), year, day, test)?
+            .is_some())
+    }
+
+    pub fn get_results(
+        &self,
+        year: i32,
+        day: u8,
+        part: Parts,
+        test: bool,
+    ) -> Result<String, AocError> {
+        self.storage()
+            .load_result(self.storage_profile(test), year, day, part, test)?
+            .ok_or_else(|| missing(result_field(part)))
+    }
+
     pub fn set_result(
         &self,
         year: i32,
@@ -182,17 +328,8 @@ impl AocDatabase {
         part: Parts,
         result: String,
     ) -> Result<(), AocError> {
-        let field = match part {
-            Parts::Part1 => "part_1",
-            Parts::Part2 => "part_2",
-        };
-        let table = match test {
-            true => Table::TestResults,
-            false => Table::Results,
-        };
-        let query = self.buld_query(table, field, Query::Insert);
-        self.execute(&query, params![year, day, result])?;
-        Ok(())
+        self.storage()
+            .store_result(self.storage_profile(test), year, day, part, test, &result)
     }
 
     pub fn has_result(
@@ -202,31 +339,29 @@ impl AocDatabase {
         part: Parts,
         test: bool,
     ) -> Result<bool, AocError> {
-        let table = match test {
-            true => "Test_Results",
-            false => "Results",
-        };
-
-        let field = match part {
-            Parts::Part1 => "part_1",
-            Parts::Part2 => "part_2",
-        };
+        Ok(self
+            .storage()
+            .load_result(self.storage_profile(test), year, day, part, test)?
+            .is_some())
+    }
 
-        let conn = self.get_conn()?;
-        match conn.query_row(
-            &format!(
-                "SELECT COUNT({field}) FROM {table} 
-                 WHERE year = ? AND day = ? 
-                 AND {field} IS NOT NULL"
-            ),
-            [year, day.into()],
-            |row| row.get::<usize, i32>(0),
-        ) {
-            Ok(count) => Ok(count > 0),
-            Err(error) => Err(AocError::Database(DatabaseError::DatabaseQuerying {
-                object: "Input".to_string(),
-                source: error,
-            })),
+    /// Parts of the year with a stored answer. The free star of the last day counts once
+    /// all other stars are collected
+    pub fn stars(&self, year: i32) -> Result<HashSet<(u8, Parts)>, AocError> {
+        let event = AocEvent::of(year);
+        let mut stars = HashSet::new();
+        for day in 1..=event.days {
+            for part in [Parts::Part1, Parts::Part2] {
+                if event.has_puzzle(day, part) && self.has_result(year, day, part, false)? {
+                    stars.insert((day, part));
+                }
+            }
+        }
+        if let Some(free_star) = event.free_star() {
+            if stars.len() + 1 == event.stars() {
+                stars.insert(free_star);
+            }
         }
+        Ok(stars)
     }
 }
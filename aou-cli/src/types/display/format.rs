@@ -0,0 +1,49 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use super::Table;
+use crate::error::AocError;
+
+/// The formats results can be printed in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Box-drawing table for the terminal
+    #[default]
+    Table,
+    /// JSON including the typed answers and every timing sample
+    Json,
+    /// CSV with one row per day and part
+    Csv,
+    /// Markdown table
+    Markdown,
+}
+
+/// Escapes a single CSV field
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Output in the machine-readable formats in addition to the terminal table
+pub trait Export: Table {
+    fn to_json(&self) -> Result<String, AocError>;
+    fn to_csv(&self) -> String;
+
+    fn to_markdown(&self) -> String {
+        self.table_constructor().markdown()
+    }
+
+    fn display(&self, format: OutputFormat) -> Result<(), AocError> {
+        match format {
+            OutputFormat::Table => self.table(),
+            OutputFormat::Json => println!("{}", self.to_json()?),
+            OutputFormat::Csv => print!("{}", self.to_csv()),
+            OutputFormat::Markdown => print!("{}", self.to_markdown()),
+        }
+        Ok(())
+    }
+}
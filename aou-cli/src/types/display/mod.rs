@@ -1,4 +1,9 @@
+mod format;
+mod progress;
 mod table;
 
+pub(crate) use format::csv_field;
+pub use format::{Export, OutputFormat};
+pub use progress::Progress;
 pub use table::Table;
 pub(crate) use table::TableStruct;
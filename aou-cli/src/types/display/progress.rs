@@ -0,0 +1,171 @@
+use std::{
+    io::{stderr, stdout, IsTerminal, Write},
+    sync::Mutex,
+    time::Instant,
+};
+
+use crate::types::{AocDuration, AocResult, Parts};
+
+#[derive(Default)]
+struct ProgressState {
+    running: Vec<(u8, Parts, Instant)>,
+    /// Finished parts which have not been drawn yet
+    finished: Vec<AocResult>,
+    /// Notes like fetching an input which have not been drawn yet
+    messages: Vec<String>,
+    /// Number of lines drawn by the last render which get overwritten by the next one
+    lines: usize,
+}
+
+impl ProgressState {
+    /// The new messages and finished parts, followed by one line per running part in live
+    /// mode. Only the running lines are redrawn, the rest scrolls up above them so the output
+    /// never grows taller than the terminal
+    fn body(&self, live: bool) -> String {
+        let mut output = String::new();
+        for message in self.messages.iter() {
+            output.push_str(&format!(
+                "{}
+",
+                message
+            ));
+        }
+        for result in self.finished.iter() {
+            output.push_str(&format!(
+                "Day {:>2} {}: {} ({})\n",
+                result.day(),
+                result.part(),
+                result.result(),
+                result.time()
+            ));
+        }
+        if live {
+            for (day, part, start) in self.running.iter() {
+                output.push_str(&format!(
+                    " Running day {} {}... {}\n",
+                    day,
+                    part,
+                    AocDuration::new(vec![start.elapsed()])
+                ));
+            }
+        }
+        output
+    }
+}
+
+/// Reports which parts are running and which results are done while a run is in progress.
+///
+/// Every finished part is reported as a line on stderr. When stdout is a terminal the running
+/// parts are redrawn in place below these lines.
+pub struct Progress {
+    live: bool,
+    state: Mutex<ProgressState>,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self {
+            live: stdout().is_terminal() && stderr().is_terminal(),
+            state: Mutex::new(ProgressState::default()),
+        }
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.live
+    }
+
+    pub fn start(&self, day: u8, part: Parts) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.running.push((day, part, Instant::now()));
+        self.draw(&mut state);
+    }
+
+    pub fn finish(&self, result: &AocResult) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .running
+            .retain(|(day, part, _)| (*day, *part) != (result.day(), result.part()));
+        state.finished.push(result.clone());
+        self.draw(&mut state);
+    }
+
+    /// Prints a note above the running parts without breaking the live display
+    pub fn message(&self, message: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.messages.push(message.to_string());
+        self.draw(&mut state);
+    }
+
+    /// Redraws the live display to update the elapsed times
+    pub fn render(&self) {
+        if self.live {
+            self.draw(&mut self.state.lock().unwrap_or_else(|e| e.into_inner()));
+        }
+    }
+
+    /// Removes the live display so the final output can take its place
+    pub fn clear(&self) {
+        if self.live {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            eprint!("{}", Self::erase(state.lines));
+            state.lines = 0;
+            let _ = stderr().flush();
+        }
+    }
+
+    fn erase(lines: usize) -> String {
+        match lines {
+            0 => "\r\x1b[J".to_string(),
+            lines => format!("\r\x1b[{}A\x1b[J", lines),
+        }
+    }
+
+    fn draw(&self, state: &mut ProgressState) {
+        let body = state.body(self.live);
+        state.messages.clear();
+        state.finished.clear();
+        if self.live {
+            eprint!("{}{}", Self::erase(state.lines), body);
+            state.lines = state.running.len();
+        } else {
+            eprint!("{}", body);
+        }
+        let _ = stderr().flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_utils::AocOption;
+    use std::time::Duration;
+
+    #[test]
+    fn line_mode_prints_only_new_lines() {
+        let result = AocResult::new(3, 1, AocOption::Int(42), vec![Duration::from_millis(5)]);
+        let state = ProgressState {
+            running: vec![(3, Parts::Part2, Instant::now())],
+            finished: vec![result.clone()],
+            messages: vec!["Fetching online...".to_string()],
+            lines: 0,
+        };
+
+        assert_eq!(
+            state.body(false),
+            format!(
+                "Fetching online...\nDay  3 Part 1: 42 ({})\n",
+                result.time()
+            )
+        );
+        let live = state.body(true);
+        assert!(live.starts_with(&state.body(false)));
+        assert!(live.contains("\n Running day 3 Part 2... "));
+        assert_eq!(live.lines().count(), 3);
+    }
+}
@@ -1,3 +1,5 @@
+use unicode_width::UnicodeWidthStr;
+
 pub trait Table {
     fn table_constructor(&self) -> TableStruct;
     fn reduced_table_constructor(&self) -> TableStruct;
@@ -10,6 +12,18 @@ pub trait Table {
     }
 }
 
+/// Pads the cell to the width of its column by the columns it takes in the terminal, which
+/// differs from its length for symbols like ⭐
+fn center(content: &str, width: usize) -> String {
+    let padding = width.saturating_sub(content.width());
+    format!(
+        "{}{}{}",
+        " ".repeat(padding / 2),
+        content,
+        " ".repeat(padding - padding / 2)
+    )
+}
+
 pub struct TableStruct {
     contents: Vec<Vec<String>>,
     cell_widths: Vec<usize>,
@@ -23,7 +37,7 @@ impl TableStruct {
         for x in 0..contents[0].len() {
             let mut width_max = 0;
             for y in contents.iter() {
-                let width = y[x].len();
+                let width = y[x].width();
                 if width > width_max {
                     width_max = width;
                 }
@@ -37,7 +51,8 @@ impl TableStruct {
             cell_widths,
         }
     }
-    pub fn display(&self) {
+    pub fn render(&self) -> String {
+        let mut output = String::new();
         for y in 0..self.height {
             for x in 0..self.width {
                 let cell_width = self.cell_widths[x];
@@ -51,19 +66,18 @@ impl TableStruct {
                         _ => "─┼",
                     },
                 };
-                print!("{}─{:─^cell_width$}", corner, "");
+                output.push_str(&format!("{}─{:─^cell_width$}", corner, ""));
             }
             let end = match y {
                 0 => '┐',
                 _ => '┤',
             };
-            println!("─{end}");
+            output.push_str(&format!("─{end}\n"));
             for x in 0..self.width {
                 let cell_width = self.cell_widths[x];
-                let content = self.contents[y][x].clone();
-                print!(" │ {:^cell_width$}", content);
+                output.push_str(&format!(" │ {}", center(&self.contents[y][x], cell_width)));
             }
-            println!(" │")
+            output.push_str(" │\n");
         }
         for x in 0..self.width {
             let cell_width = self.cell_widths[x];
@@ -71,8 +85,44 @@ impl TableStruct {
                 0 => " └",
                 _ => "─┴",
             };
-            print!("{}─{:─^cell_width$}", corner, "");
+            output.push_str(&format!("{}─{:─^cell_width$}", corner, ""));
+        }
+        output.push_str("─┘\n");
+        output
+    }
+
+    pub fn display(&self) {
+        print!("{}", self.render());
+    }
+
+    pub fn markdown(&self) -> String {
+        let escape = |cell: &String| cell.replace('|', "\\|").replace('\n', "<br>");
+        let mut output = String::new();
+        for (y, row) in self.contents.iter().enumerate() {
+            let cells: Vec<String> = row.iter().map(escape).collect();
+            output.push_str(&format!("| {} |\n", cells.join(" | ")));
+            if y == 0 {
+                let separator: Vec<&str> = row.iter().map(|_| "---").collect();
+                output.push_str(&format!("| {} |\n", separator.join(" | ")));
+            }
         }
-        println!("─┘")
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_symbols_keep_columns_aligned() {
+        let table = TableStruct::new(vec![
+            vec!["Part".to_string(), "Answer".to_string()],
+            vec!["1".to_string(), "⭐".to_string()],
+            vec!["2".to_string(), "✓".to_string()],
+        ]);
+
+        let widths: Vec<usize> = table.render().lines().map(|line| line.width()).collect();
+        assert!(widths.iter().all(|width| *width == widths[0]));
     }
 }
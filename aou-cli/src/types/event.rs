@@ -0,0 +1,79 @@
+use super::Parts;
+
+/// First year with the shortened calendar of 12 days
+const SHORT_CALENDAR_YEAR: i32 = 2025;
+
+/// The calendar of Advent of Code in a year
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AocEvent {
+    /// Number of puzzles, released one per day from December 1st
+    pub days: u8,
+    /// Whether the last day has a second puzzle. If not, its second star is awarded for
+    /// collecting all other stars
+    pub final_part_2: bool,
+}
+
+impl AocEvent {
+    /// The calendar of the given year, assuming later years keep the latest one
+    pub fn of(year: i32) -> Self {
+        match year {
+            year if year < SHORT_CALENDAR_YEAR => Self {
+                days: 25,
+                final_part_2: false,
+            },
+            _ => Self {
+                days: 12,
+                final_part_2: false,
+            },
+        }
+    }
+
+    /// Checks if the day is part of the calendar
+    pub fn has_day(&self, day: u8) -> bool {
+        (1..=self.days).contains(&day)
+    }
+
+    /// Checks if the part of the day is a puzzle which has to be solved
+    pub fn has_puzzle(&self, day: u8, part: Parts) -> bool {
+        self.has_day(day) && (part == Parts::Part1 || day != self.days || self.final_part_2)
+    }
+
+    /// The part awarded for collecting all other stars instead of being solved
+    pub fn free_star(&self) -> Option<(u8, Parts)> {
+        (!self.final_part_2).then_some((self.days, Parts::Part2))
+    }
+
+    /// Stars which can be collected, two per day
+    pub fn stars(&self) -> usize {
+        self.days as usize * 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calendar_per_year() {
+        for year in 2015..=2024 {
+            let event = AocEvent::of(year);
+            assert_eq!(event.days, 25, "{year}");
+            assert_eq!(event.stars(), 50, "{year}");
+            assert!(event.has_puzzle(25, Parts::Part1), "{year}");
+            assert!(!event.has_puzzle(25, Parts::Part2), "{year}");
+            assert!(event.has_puzzle(24, Parts::Part2), "{year}");
+            assert!(!event.has_day(26), "{year}");
+            assert!(event.free_star() == Some((25, Parts::Part2)), "{year}");
+        }
+
+        let event = AocEvent::of(2025);
+        assert_eq!(event.days, 12);
+        assert_eq!(event.stars(), 24);
+        assert!(event.has_puzzle(11, Parts::Part2));
+        assert!(event.has_puzzle(12, Parts::Part1));
+        assert!(!event.has_puzzle(12, Parts::Part2));
+        assert!(!event.has_day(13));
+        assert!(!event.has_day(0));
+        assert!(event.free_star() == Some((12, Parts::Part2)));
+    }
+}
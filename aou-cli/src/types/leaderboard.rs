@@ -0,0 +1,296 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::{
+    display::{Table, TableStruct},
+    AocEvent, Parts,
+};
+
+/// Timestamps are shown in EST, the time zone the puzzles are released in
+const EST_OFFSET_SECONDS: i32 = -5 * 3600;
+const TIMESTAMP_FORMAT: &str = "%m-%d %H:%M";
+
+#[derive(Deserialize)]
+struct StarRecord {
+    get_star_ts: i64,
+}
+
+#[derive(Deserialize)]
+struct MemberRecord {
+    id: u64,
+    name: Option<String>,
+    stars: u32,
+    local_score: u64,
+    #[serde(default)]
+    last_star_ts: i64,
+    #[serde(default)]
+    completion_day_level: HashMap<u8, HashMap<u8, StarRecord>>,
+}
+
+/// The JSON Advent of Code serves for a private leaderboard
+#[derive(Deserialize)]
+struct LeaderboardRecord {
+    members: HashMap<String, MemberRecord>,
+}
+
+/// A member of a private leaderboard
+pub struct AocMember {
+    pub id: u64,
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u64,
+    last_star_ts: i64,
+    /// When each part was solved
+    completions: HashMap<(u8, Parts), DateTime<Utc>>,
+}
+
+impl AocMember {
+    /// The name, or how Advent of Code shows members without one
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    pub fn completed_at(&self, day: u8, part: Parts) -> Option<DateTime<Utc>> {
+        self.completions.get(&(day, part)).copied()
+    }
+}
+
+/// A private leaderboard with its members ordered by their local score
+pub struct AocLeaderboard {
+    year: i32,
+    members: Vec<AocMember>,
+}
+
+impl AocLeaderboard {
+    pub fn from_json(year: i32, json: &str) -> Result<Self, serde_json::Error> {
+        let record: LeaderboardRecord = serde_json::from_str(json)?;
+        let mut members: Vec<AocMember> = record
+            .members
+            .into_values()
+            .map(|member| AocMember {
+                id: member.id,
+                name: member.name,
+                stars: member.stars,
+                local_score: member.local_score,
+                last_star_ts: member.last_star_ts,
+                completions: member
+                    .completion_day_level
+                    .into_iter()
+                    .flat_map(|(day, parts)| {
+                        parts.into_iter().filter_map(move |(part, star)| {
+                            let part = Parts::new(part).ok()?;
+                            let time = DateTime::from_timestamp(star.get_star_ts, 0)?;
+                            Some(((day, part), time))
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+        // Ties go to whoever got their last star first, like on the website
+        members.sort_by_key(|member| {
+            (
+                std::cmp::Reverse(member.local_score),
+                std::cmp::Reverse(member.stars),
+                member.last_star_ts,
+                member.id,
+            )
+        });
+
+        Ok(Self { year, members })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn members(&self) -> &[AocMember] {
+        &self.members
+    }
+
+    /// Marks the stars of every day of the calendar: `*` for both, `+` for the first, `.` for none
+    fn stars_cell(&self, member: &AocMember) -> String {
+        (1..=AocEvent::of(self.year).days)
+            .map(|day| {
+                match (
+                    member.completed_at(day, Parts::Part1),
+                    member.completed_at(day, Parts::Part2),
+                ) {
+                    (Some(_), Some(_)) => '*',
+                    (Some(_), None) | (None, Some(_)) => '+',
+                    (None, None) => '.',
+                }
+            })
+            .collect()
+    }
+
+    /// When the part was solved, in EST
+    fn completion_cell(member: &AocMember, day: u8, part: Parts) -> String {
+        let est = FixedOffset::east_opt(EST_OFFSET_SECONDS).expect("Invalid EST offset");
+        member
+            .completed_at(day, part)
+            .map(|time| {
+                time.with_timezone(&est)
+                    .format(TIMESTAMP_FORMAT)
+                    .to_string()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The members with the times they solved both parts of a day
+    pub fn day_table_constructor(&self, day: u8) -> TableStruct {
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "#".to_string(),
+            "Member".to_string(),
+            "Score".to_string(),
+            "Stars".to_string(),
+            format!("Day {day} Part 1"),
+            format!("Day {day} Part 2"),
+        ]];
+        for (rank, member) in self.members.iter().enumerate() {
+            contents.push(vec![
+                (rank + 1).to_string(),
+                member.display_name(),
+                member.local_score.to_string(),
+                member.stars.to_string(),
+                Self::completion_cell(member, day, Parts::Part1),
+                Self::completion_cell(member, day, Parts::Part2),
+            ])
+        }
+        TableStruct::new(contents)
+    }
+
+    pub fn day_table(&self, day: u8) {
+        self.day_table_constructor(day).display();
+    }
+}
+
+impl Table for AocLeaderboard {
+    /// One character per day keeps the table narrow, `day_table` shows the times of a day
+    fn table_constructor(&self) -> TableStruct {
+        // The last digit of every day, like the calendar on the website
+        let days: String = (1..=AocEvent::of(self.year).days)
+            .map(|day| char::from(b'0' + day % 10))
+            .collect();
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "#".to_string(),
+            "Member".to_string(),
+            "Score".to_string(),
+            "Stars".to_string(),
+            days,
+        ]];
+        for (rank, member) in self.members.iter().enumerate() {
+            contents.push(vec![
+                (rank + 1).to_string(),
+                member.display_name(),
+                member.local_score.to_string(),
+                member.stars.to_string(),
+                self.stars_cell(member),
+            ]);
+        }
+        TableStruct::new(contents)
+    }
+
+    fn reduced_table_constructor(&self) -> TableStruct {
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "#".to_string(),
+            "Member".to_string(),
+            "Score".to_string(),
+            "Stars".to_string(),
+        ]];
+        for (rank, member) in self.members.iter().enumerate() {
+            contents.push(vec![
+                (rank + 1).to_string(),
+                member.display_name(),
+                member.local_score.to_string(),
+                member.stars.to_string(),
+            ])
+        }
+        TableStruct::new(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unicode_width::UnicodeWidthStr;
+
+    const LEADERBOARD: &str = r#"{
+        "owner_id": 1,
+        "event": "2023",
+        "members": {
+            "1": {
+                "id": 1, "name": "alice", "stars": 3, "local_score": 6,
+                "global_score": 0, "last_star_ts": 1701410000,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1701407520, "star_index": 1},
+                        "2": {"get_star_ts": 1701410000, "star_index": 2}
+                    },
+                    "2": {"1": {"get_star_ts": 1701496800, "star_index": 3}}
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "stars": 0, "local_score": 0,
+                "global_score": 0, "last_star_ts": 0, "completion_day_level": {}
+            },
+            "3": {
+                "id": 3, "name": "bob", "stars": 3, "local_score": 6,
+                "global_score": 0, "last_star_ts": 1701500000,
+                "completion_day_level": {
+                    "1": {"1": {"get_star_ts": 1701408000, "star_index": 1}},
+                    "3": {
+                        "1": {"get_star_ts": 1701495000, "star_index": 2},
+                        "2": {"get_star_ts": 1701500000, "star_index": 3}
+                    }
+                }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn orders_members_and_formats_completions() {
+        let leaderboard = AocLeaderboard::from_json(2023, LEADERBOARD).unwrap();
+        let names: Vec<String> = leaderboard
+            .members()
+            .iter()
+            .map(AocMember::display_name)
+            .collect();
+        assert_eq!(names, ["alice", "bob", "(anonymous user #2)"]);
+
+        let alice = &leaderboard.members()[0];
+        assert_eq!(
+            AocLeaderboard::completion_cell(alice, 1, Parts::Part1),
+            "12-01 00:12"
+        );
+        assert_eq!(
+            AocLeaderboard::completion_cell(alice, 1, Parts::Part2),
+            "12-01 00:53"
+        );
+        assert_eq!(AocLeaderboard::completion_cell(alice, 3, Parts::Part1), "");
+    }
+
+    #[test]
+    fn marks_stars_per_day() {
+        let leaderboard = AocLeaderboard::from_json(2023, LEADERBOARD).unwrap();
+        let cells: Vec<String> = leaderboard
+            .members()
+            .iter()
+            .map(|member| leaderboard.stars_cell(member))
+            .collect();
+
+        assert_eq!(cells[0], format!("*+{}", ".".repeat(23)));
+        assert_eq!(cells[1], format!("+.*{}", ".".repeat(22)));
+        assert_eq!(cells[2], ".".repeat(25));
+        let table = leaderboard.table_constructor().render();
+        assert!(table.lines().all(|line| line.width() < 80));
+    }
+
+    #[test]
+    fn rejects_other_responses() {
+        assert!(AocLeaderboard::from_json(2023, "<!DOCTYPE html>").is_err());
+    }
+}
@@ -1,12 +1,30 @@
+mod benchmark;
+mod cache;
+mod calendar;
 mod db;
 pub mod display;
+mod event;
+mod leaderboard;
+mod normalization;
 mod parts;
+mod requests;
 mod result;
+mod storage;
 mod time;
 
-pub use db::AocDatabase;
+pub use benchmark::AocBenchmark;
+pub use cache::{
+    AocCache, AocCacheArchive, AocCacheEntry, AocInputCheck, AocInputReport, InputStatus,
+};
+pub use calendar::AocCalendar;
+pub use db::{AocDatabase, DEFAULT_PROFILE};
+pub use event::AocEvent;
+pub use leaderboard::{AocLeaderboard, AocMember};
+pub use normalization::InputNormalization;
 pub use parts::Parts;
+pub use requests::{AocRequest, AocRequestLog};
 pub use result::{AocResult, AocYear};
-pub use time::AocTime;
+pub use storage::{is_encrypted, AocStorage, EncryptedStorage, FileStorage};
+pub use time::{AocDuration, AocTime, Clock, ShiftedClock, SystemClock, AOC_START_YEAR};
 
 pub(crate) use std::fmt::Display;
@@ -0,0 +1,70 @@
+/// How an input is changed before it is handed to a solution
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputNormalization {
+    /// Remove trailing newlines
+    pub trim_newline: bool,
+    /// Replace `\r\n` line endings with `\n`
+    pub normalize_crlf: bool,
+}
+
+impl InputNormalization {
+    pub fn new(trim_newline: bool, normalize_crlf: bool) -> Self {
+        Self {
+            trim_newline,
+            normalize_crlf,
+        }
+    }
+
+    pub fn apply(&self, mut input: String) -> String {
+        if self.normalize_crlf {
+            input = input.replace("\r\n", "\n");
+        }
+        if self.trim_newline {
+            input.truncate(input.trim_end_matches(['\n', '\r']).len());
+        }
+        input
+    }
+}
+
+impl std::fmt::Display for InputNormalization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.trim_newline, self.normalize_crlf) {
+            (false, false) => write!(f, "none"),
+            (true, false) => write!(f, "trim trailing newlines"),
+            (false, true) => write!(f, "normalize CRLF"),
+            (true, true) => write!(f, "normalize CRLF, trim trailing newlines"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_passes_input_unchanged() {
+        let input = "1\r\n2\r\n\n".to_string();
+
+        assert_eq!(InputNormalization::default().apply(input.clone()), input);
+    }
+
+    #[test]
+    fn normalizes_line_endings() {
+        let crlf = InputNormalization::new(false, true);
+
+        assert_eq!(crlf.apply("1\r\n2\r\n".to_string()), "1\n2\n");
+        assert_eq!(crlf.apply("a\rb".to_string()), "a\rb");
+    }
+
+    #[test]
+    fn trims_trailing_newlines() {
+        let trim = InputNormalization::new(true, false);
+
+        assert_eq!(trim.apply("1\n2\n\n".to_string()), "1\n2");
+        assert_eq!(trim.apply("1\r\n2\r\n".to_string()), "1\r\n2");
+        assert_eq!(
+            InputNormalization::new(true, true).apply("1\r\n2\r\n".to_string()),
+            "1\n2"
+        );
+    }
+}
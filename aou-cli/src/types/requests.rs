@@ -0,0 +1,65 @@
+use super::display::{Table, TableStruct};
+
+/// A request made to Advent of Code as stored in the request log
+pub struct AocRequest {
+    pub timestamp: String,
+    pub method: String,
+    pub url: String,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// The latest entries of the request log
+pub struct AocRequestLog {
+    requests: Vec<AocRequest>,
+}
+
+impl AocRequestLog {
+    pub fn new(requests: Vec<AocRequest>) -> Self {
+        Self { requests }
+    }
+}
+
+impl Table for AocRequestLog {
+    fn table_constructor(&self) -> TableStruct {
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "Time".to_string(),
+            "Method".to_string(),
+            "URL".to_string(),
+            "Status".to_string(),
+            "Error".to_string(),
+        ]];
+        for request in self.requests.iter() {
+            contents.push(vec![
+                request.timestamp.clone(),
+                request.method.clone(),
+                request.url.clone(),
+                request
+                    .status
+                    .map(|s| s.to_string())
+                    .unwrap_or("None".to_string()),
+                request.error.clone().unwrap_or_default(),
+            ])
+        }
+        TableStruct::new(contents)
+    }
+
+    fn reduced_table_constructor(&self) -> TableStruct {
+        let mut contents: Vec<Vec<String>> = vec![vec![
+            "Time".to_string(),
+            "URL".to_string(),
+            "Status".to_string(),
+        ]];
+        for request in self.requests.iter() {
+            contents.push(vec![
+                request.timestamp.clone(),
+                request.url.clone(),
+                request
+                    .status
+                    .map(|s| s.to_string())
+                    .unwrap_or("None".to_string()),
+            ])
+        }
+        TableStruct::new(contents)
+    }
+}
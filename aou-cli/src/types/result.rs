@@ -3,11 +3,15 @@ use std::{
     time::Duration,
 };
 
+use serde::{Serialize, Serializer};
+
 use super::{
-    display::{Table, TableStruct},
+    calendar::STAR,
+    display::{csv_field, Export, Table, TableStruct},
     time::AocDuration,
-    Parts,
+    AocEvent, Parts,
 };
+use crate::error::AocError;
 use advent_of_utils::AocOption;
 
 #[derive(Clone, Hash, Eq, PartialEq)]
@@ -37,15 +41,65 @@ impl AocResult {
     pub fn result(&self) -> &AocOption {
         &self.result
     }
+    pub fn time(&self) -> &AocDuration {
+        &self.time
+    }
+}
+
+/// Name of the `AocOption` variant used in the machine-readable output
+fn option_kind(option: &AocOption) -> &'static str {
+    match option {
+        AocOption::Str(_) => "str",
+        AocOption::Int(_) => "int",
+        AocOption::None => "none",
+    }
+}
+
+#[derive(Serialize)]
+struct AocResultRecord {
+    day: u8,
+    part: u8,
+    answer: String,
+    kind: &'static str,
+    value: serde_json::Value,
+    avg_ns: Option<u64>,
+    samples_ns: Vec<u64>,
+}
+
+impl Serialize for AocResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AocResultRecord {
+            day: self.day,
+            part: self.part.as_number(),
+            answer: self.result.to_string(),
+            kind: option_kind(&self.result),
+            value: match &self.result {
+                AocOption::Str(s) => s.clone().into(),
+                AocOption::Int(i) => (*i).into(),
+                AocOption::None => serde_json::Value::Null,
+            },
+            avg_ns: self.time.avg_time().map(|d| d.as_nanos() as u64),
+            samples_ns: self
+                .time
+                .duration()
+                .iter()
+                .map(|d| d.as_nanos() as u64)
+                .collect(),
+        }
+        .serialize(serializer)
+    }
 }
 
 pub struct AocYear {
     days: HashMap<(u8, Parts), AocResult>,
     available_days: BTreeSet<u8>,
+    event: AocEvent,
+    /// Whether all other stars of the year are collected
+    free_star: bool,
 }
 
 impl AocYear {
-    pub fn from_vec(results: Vec<AocResult>) -> Self {
+    pub fn from_vec(year: i32, results: Vec<AocResult>) -> Self {
         let mut days: HashMap<(u8, Parts), AocResult> = HashMap::new();
         let mut available_days = BTreeSet::new();
         for mut result in results {
@@ -66,9 +120,36 @@ impl AocYear {
         Self {
             days,
             available_days,
+            event: AocEvent::of(year),
+            free_star: false,
+        }
+    }
+
+    /// Marks the free star of the last day as earned
+    pub fn with_free_star(mut self, earned: bool) -> Self {
+        self.free_star = earned;
+        self
+    }
+
+    /// Answer of a part for the tables, parts without a puzzle show a dash if they gave nothing
+    /// and the free star shows a star once it is earned
+    fn answer_cell(&self, day: u8, part: Parts) -> String {
+        if self.free_star && self.event.free_star() == Some((day, part)) {
+            return STAR.to_string();
+        }
+        match self.days.get(&(day, part)).map(|r| &r.result) {
+            Some(AocOption::None) | None if !self.event.has_puzzle(day, part) => "-".to_string(),
+            Some(result) => result.to_string(),
+            None => AocOption::None.to_string(),
         }
     }
-    fn calculate_total_time(&self) -> AocDuration {
+    /// Returns all results ordered by day and part
+    pub fn results(&self) -> Vec<&AocResult> {
+        let mut results: Vec<&AocResult> = self.days.values().collect();
+        results.sort_by_key(|r| (r.day(), r.part().as_number()));
+        results
+    }
+    pub fn calculate_total_time(&self) -> AocDuration {
         let mut total_durations = vec![];
 
         // For each run, sum up all times
@@ -108,16 +189,8 @@ impl Table for AocYear {
             let part2 = (*day, Parts::Part2);
             contents.push(vec![
                 day.to_string(),
-                results
-                    .get(&part1)
-                    .map(|r| r.result.clone())
-                    .unwrap_or(AocOption::None)
-                    .to_string(),
-                results
-                    .get(&part2)
-                    .map(|r| r.result.clone())
-                    .unwrap_or(AocOption::None)
-                    .to_string(),
+                self.answer_cell(*day, Parts::Part1),
+                self.answer_cell(*day, Parts::Part2),
                 results
                     .get(&part1)
                     .map(|r| r.time.clone())
@@ -139,24 +212,45 @@ impl Table for AocYear {
             "Part 1".to_string(),
             "Part 2".to_string(),
         ]];
-        let results = self.days.clone();
         for day in self.available_days.iter() {
-            let part1 = (*day, Parts::Part1);
-            let part2 = (*day, Parts::Part2);
             contents.push(vec![
                 day.to_string(),
-                results
-                    .get(&part1)
-                    .map(|r| r.result.clone())
-                    .unwrap_or(AocOption::None)
-                    .to_string(),
-                results
-                    .get(&part2)
-                    .map(|r| r.result.clone())
-                    .unwrap_or(AocOption::None)
-                    .to_string(),
+                self.answer_cell(*day, Parts::Part1),
+                self.answer_cell(*day, Parts::Part2),
             ])
         }
         TableStruct::new(contents)
     }
 }
+
+impl Export for AocYear {
+    fn to_json(&self) -> Result<String, AocError> {
+        Ok(serde_json::to_string_pretty(&self.results())?)
+    }
+
+    fn to_csv(&self) -> String {
+        let mut output = "day,part,kind,answer,avg_ns,samples_ns\n".to_string();
+        for result in self.results() {
+            output.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                result.day,
+                result.part.as_number(),
+                option_kind(&result.result),
+                csv_field(&result.result.to_string()),
+                result
+                    .time
+                    .avg_time()
+                    .map(|d| d.as_nanos().to_string())
+                    .unwrap_or_default(),
+                result
+                    .time
+                    .duration()
+                    .iter()
+                    .map(|d| d.as_nanos().to_string())
+                    .collect::<Vec<_>>()
+                    .join(";"),
+            ));
+        }
+        output
+    }
+}
@@ -0,0 +1,252 @@
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use super::AocStorage;
+use crate::{
+    error::{AocError, InputError},
+    types::AocCacheEntry,
+    Parts,
+};
+
+/// Marks an encrypted input, followed by the base64 encoded salt, nonce and ciphertext
+const ENCRYPTED_PREFIX: &str = "aou-encrypted:v1:";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Checks if a stored input is encrypted
+pub fn is_encrypted(input: &str) -> bool {
+    input.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Encrypts the inputs before they reach another backend and decrypts them when they are
+/// loaded. Answers are stored unchanged.
+///
+/// The key is derived from a secret, like a passphrase or the contents of a key file, with
+/// Argon2id. Inputs which are not encrypted are loaded as they are.
+pub struct EncryptedStorage {
+    inner: Arc<dyn AocStorage>,
+    secret: Vec<u8>,
+    /// Salt used for everything encrypted by this instance
+    salt: [u8; SALT_LEN],
+    /// Derived keys by salt, as deriving is slow on purpose
+    keys: Mutex<HashMap<[u8; SALT_LEN], Key>>,
+}
+
+impl EncryptedStorage {
+    pub fn new(inner: Arc<dyn AocStorage>, secret: &[u8]) -> Self {
+        let mut salt = [0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            inner,
+            secret: secret.to_vec(),
+            salt,
+            keys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn cipher(&self, salt: [u8; SALT_LEN]) -> Result<ChaCha20Poly1305, AocError> {
+        let mut keys = self.keys.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(key) = keys.get(&salt) {
+            return Ok(ChaCha20Poly1305::new(key));
+        }
+
+        let mut key = Key::default();
+        Argon2::default()
+            .hash_password_into(&self.secret, &salt, &mut key)
+            .map_err(|error| {
+                AocError::Input(InputError::EncryptionFailed {
+                    reason: error.to_string(),
+                })
+            })?;
+        keys.insert(salt, key);
+        Ok(ChaCha20Poly1305::new(&key))
+    }
+
+    fn encrypt(&self, input: &str) -> Result<String, AocError> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher(self.salt)?
+            .encrypt(&nonce, input.as_bytes())
+            .map_err(|error| {
+                AocError::Input(InputError::EncryptionFailed {
+                    reason: error.to_string(),
+                })
+            })?;
+
+        let mut data = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+        data.extend_from_slice(&self.salt);
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(data)))
+    }
+
+    fn decrypt(&self, year: i32, day: u8, stored: String) -> Result<String, AocError> {
+        let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(stored);
+        };
+        let failed = || AocError::Input(InputError::DecryptionFailed { year, day });
+
+        let data = STANDARD.decode(encoded.trim()).map_err(|_| failed())?;
+        if data.len() < SALT_LEN + NONCE_LEN {
+            return Err(failed());
+        }
+        let (salt, rest) = data.split_at(SALT_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let salt = salt.try_into().map_err(|_| failed())?;
+        let plaintext = self
+            .cipher(salt)?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| failed())?;
+        String::from_utf8(plaintext).map_err(|_| failed())
+    }
+}
+
+impl AocStorage for EncryptedStorage {
+    fn load_input(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        test: bool,
+    ) -> Result<Option<String>, AocError> {
+        self.inner
+            .load_input(profile, year, day, test)?
+            .map(|stored| self.decrypt(year, day, stored))
+            .transpose()
+    }
+
+    fn store_input(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        test: bool,
+        input: &str,
+    ) -> Result<(), AocError> {
+        self.inner
+            .store_input(profile, year, day, test, &self.encrypt(input)?)
+    }
+
+    fn load_result(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        part: Parts,
+        test: bool,
+    ) -> Result<Option<String>, AocError> {
+        self.inner.load_result(profile, year, day, part, test)
+    }
+
+    fn store_result(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        part: Parts,
+        test: bool,
+        result: &str,
+    ) -> Result<(), AocError> {
+        self.inner
+            .store_result(profile, year, day, part, test, result)
+    }
+
+    fn list(&self, profile: &str, year: Option<i32>) -> Result<Vec<AocCacheEntry>, AocError> {
+        self.inner.list(profile, year)
+    }
+
+    fn clear(
+        &self,
+        profile: &str,
+        year: Option<i32>,
+        day: Option<u8>,
+        answers: bool,
+    ) -> Result<usize, AocError> {
+        self.inner.clear(profile, year, day, answers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AocDatabase;
+
+    fn encrypted(db: &AocDatabase, secret: &str) -> AocDatabase {
+        let storage = EncryptedStorage::new(Arc::new(db.clone()), secret.as_bytes());
+        db.clone().with_storage(Arc::new(storage))
+    }
+
+    #[test]
+    fn stores_only_ciphertext() {
+        let db = AocDatabase::in_memory().unwrap();
+        let keyed = encrypted(&db, "hunter2");
+        keyed
+            .set_input(2023, 1, false, "secret input".to_string())
+            .unwrap();
+        keyed
+            .set_result(2023, 1, false, Parts::Part1, "42".to_string())
+            .unwrap();
+
+        assert_eq!(keyed.get_input(2023, 1, false).unwrap(), "secret input");
+        let stored = db.load_input("default", 2023, 1, false).unwrap().unwrap();
+        assert!(is_encrypted(&stored));
+        assert!(!stored.contains("secret input"));
+        assert_eq!(db.get_results(2023, 1, Parts::Part1, false).unwrap(), "42");
+    }
+
+    #[test]
+    fn other_instance_with_same_secret_decrypts() {
+        let db = AocDatabase::in_memory().unwrap();
+        encrypted(&db, "hunter2")
+            .set_input(2023, 1, true, "test input".to_string())
+            .unwrap();
+
+        assert_eq!(
+            encrypted(&db, "hunter2").get_input(2023, 1, true).unwrap(),
+            "test input"
+        );
+    }
+
+    #[test]
+    fn wrong_or_missing_secret_fails() {
+        let db = AocDatabase::in_memory().unwrap();
+        encrypted(&db, "hunter2")
+            .set_input(2023, 1, false, "secret input".to_string())
+            .unwrap();
+
+        assert!(matches!(
+            encrypted(&db, "hunter3").get_input(2023, 1, false),
+            Err(AocError::Input(InputError::DecryptionFailed {
+                year: 2023,
+                day: 1
+            }))
+        ));
+        assert!(matches!(
+            db.get_input(2023, 1, false),
+            Err(AocError::Input(InputError::Encrypted {
+                year: 2023,
+                day: 1
+            }))
+        ));
+    }
+
+    #[test]
+    fn loads_plain_inputs() {
+        let db = AocDatabase::in_memory().unwrap();
+        db.set_input(2023, 1, false, "plain".to_string()).unwrap();
+
+        assert_eq!(
+            encrypted(&db, "hunter2").get_input(2023, 1, false).unwrap(),
+            "plain"
+        );
+    }
+}
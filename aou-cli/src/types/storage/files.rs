@@ -0,0 +1,381 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::AocStorage;
+use crate::{
+    error::{AocError, InputError},
+    types::{AocCacheEntry, DEFAULT_PROFILE},
+    Parts,
+};
+
+/// Contents of `answers.toml` and `test_answers.toml`
+#[derive(Serialize, Deserialize, Default)]
+struct Answers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part_1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part_2: Option<String>,
+}
+
+impl Answers {
+    fn part(&mut self, part: Parts) -> &mut Option<String> {
+        match part {
+            Parts::Part1 => &mut self.part_1,
+            Parts::Part2 => &mut self.part_2,
+        }
+    }
+}
+
+/// Keeps the inputs and answers as plain files, e.g. to commit them to a private repository:
+///
+/// ```text
+/// <dir>/2023/05/input.txt
+/// <dir>/2023/05/answers.toml
+/// <dir>/2023/05/test_input.txt
+/// <dir>/2023/05/test_answers.toml
+/// <dir>/2023/05/profiles/<profile>/input.txt
+/// ```
+///
+/// Profiles other than the default one get their own directory per day.
+pub struct FileStorage {
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    fn day_dir(&self, profile: &str, year: i32, day: u8) -> PathBuf {
+        let dir = self.dir.join(year.to_string()).join(format!("{:02}", day));
+        match profile {
+            DEFAULT_PROFILE => dir,
+            profile => dir.join("profiles").join(profile),
+        }
+    }
+
+    fn input_path(&self, profile: &str, year: i32, day: u8, test: bool) -> PathBuf {
+        let file = match test {
+            true => "test_input.txt",
+            false => "input.txt",
+        };
+        self.day_dir(profile, year, day).join(file)
+    }
+
+    fn answers_path(&self, profile: &str, year: i32, day: u8, test: bool) -> PathBuf {
+        let file = match test {
+            true => "test_answers.toml",
+            false => "answers.toml",
+        };
+        self.day_dir(profile, year, day).join(file)
+    }
+
+    /// Directories below `dir` whose names parse as numbers, ordered by the number
+    fn numbered<T: std::str::FromStr + Ord>(dir: &Path) -> Result<Vec<T>, AocError> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => {
+                return Err(AocError::Input(InputError::FileReadError {
+                    path: dir.to_path_buf(),
+                    reason: error.to_string(),
+                    source: Some(error),
+                }))
+            }
+        };
+        let mut numbers = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+            .collect::<Vec<T>>();
+        numbers.sort();
+        Ok(numbers)
+    }
+
+    fn read_answers(path: &Path) -> Result<Answers, AocError> {
+        match read(path)? {
+            Some(content) => toml::from_str(&content).map_err(|error| {
+                AocError::Input(InputError::FileReadError {
+                    path: path.to_path_buf(),
+                    reason: error.to_string(),
+                    source: None,
+                })
+            }),
+            None => Ok(Answers::default()),
+        }
+    }
+}
+
+/// Reads a file, `None` if it does not exist
+fn read(path: &Path) -> Result<Option<String>, AocError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(AocError::Input(InputError::FileReadError {
+            path: path.to_path_buf(),
+            reason: error.to_string(),
+            source: Some(error),
+        })),
+    }
+}
+
+/// Writes a file, creating its directory if needed
+fn write(path: &Path, content: &str) -> Result<(), AocError> {
+    let save_error = |error: io::Error| {
+        AocError::Input(InputError::FileSaveError {
+            path: path.to_path_buf(),
+            reason: error.to_string(),
+            source: Some(error),
+        })
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(save_error)?;
+    }
+    fs::write(path, content).map_err(save_error)
+}
+
+impl AocStorage for FileStorage {
+    fn load_input(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        test: bool,
+    ) -> Result<Option<String>, AocError> {
+        read(&self.input_path(profile, year, day, test))
+    }
+
+    fn store_input(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        test: bool,
+        input: &str,
+    ) -> Result<(), AocError> {
+        write(&self.input_path(profile, year, day, test), input)
+    }
+
+    fn load_result(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        part: Parts,
+        test: bool,
+    ) -> Result<Option<String>, AocError> {
+        let path = self.answers_path(profile, year, day, test);
+        Ok(Self::read_answers(&path)?.part(part).take())
+    }
+
+    fn store_result(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        part: Parts,
+        test: bool,
+        result: &str,
+    ) -> Result<(), AocError> {
+        let path = self.answers_path(profile, year, day, test);
+        let mut answers = Self::read_answers(&path)?;
+        *answers.part(part) = Some(result.to_string());
+
+        let content = toml::to_string(&answers).map_err(|error| {
+            AocError::Input(InputError::FileSaveError {
+                path: path.clone(),
+                reason: error.to_string(),
+                source: None,
+            })
+        })?;
+        write(&path, &content)
+    }
+
+    fn list(&self, profile: &str, year: Option<i32>) -> Result<Vec<AocCacheEntry>, AocError> {
+        let years = match year {
+            Some(year) => vec![year],
+            None => Self::numbered(&self.dir)?,
+        };
+
+        let mut entries = Vec::new();
+        for year in years {
+            for day in Self::numbered::<u8>(&self.dir.join(year.to_string()))? {
+                let mut answers =
+                    Self::read_answers(&self.answers_path(profile, year, day, false))?;
+                let entry = AocCacheEntry {
+                    year,
+                    day,
+                    input: self.input_path(profile, year, day, false).is_file(),
+                    test_input: self.input_path(DEFAULT_PROFILE, year, day, true).is_file(),
+                    part_1: answers.part(Parts::Part1).is_some(),
+                    part_2: answers.part(Parts::Part2).is_some(),
+                };
+                if entry.input || entry.test_input || entry.part_1 || entry.part_2 {
+                    entries.push(entry);
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    fn clear(
+        &self,
+        profile: &str,
+        year: Option<i32>,
+        day: Option<u8>,
+        answers: bool,
+    ) -> Result<usize, AocError> {
+        let mut paths = Vec::new();
+        for entry in self.list(profile, year)? {
+            if day.is_some_and(|day| day != entry.day) {
+                continue;
+            }
+            if entry.input {
+                paths.push(self.input_path(profile, entry.year, entry.day, false));
+            }
+            if answers && (entry.part_1 || entry.part_2) {
+                paths.push(self.answers_path(profile, entry.year, entry.day, false));
+            }
+        }
+
+        for path in paths.iter() {
+            fs::remove_file(path).map_err(|error| {
+                AocError::Input(InputError::FileSaveError {
+                    path: path.clone(),
+                    reason: error.to_string(),
+                    source: Some(error),
+                })
+            })?;
+        }
+        Ok(paths.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AocDatabase;
+    use tempfile::TempDir;
+
+    /// Stores and loads the same values and returns everything loaded
+    fn exercise(storage: &dyn AocStorage) -> Vec<Option<String>> {
+        storage
+            .store_input("default", 2023, 5, false, "input\n")
+            .unwrap();
+        storage
+            .store_input("default", 2023, 5, true, "test")
+            .unwrap();
+        storage.store_input("bob", 2023, 5, false, "bob").unwrap();
+        storage
+            .store_input("default", 2023, 5, false, "new input")
+            .unwrap();
+        storage
+            .store_result("default", 2023, 5, Parts::Part2, false, "42")
+            .unwrap();
+        storage
+            .store_result("default", 2023, 5, Parts::Part1, true, "1")
+            .unwrap();
+
+        vec![
+            storage.load_input("default", 2023, 5, false).unwrap(),
+            storage.load_input("default", 2023, 5, true).unwrap(),
+            storage.load_input("bob", 2023, 5, false).unwrap(),
+            storage.load_input("bob", 2023, 5, true).unwrap(),
+            storage.load_input("default", 2023, 6, false).unwrap(),
+            storage
+                .load_result("default", 2023, 5, Parts::Part1, false)
+                .unwrap(),
+            storage
+                .load_result("default", 2023, 5, Parts::Part2, false)
+                .unwrap(),
+            storage
+                .load_result("default", 2023, 5, Parts::Part1, true)
+                .unwrap(),
+            storage
+                .load_result("bob", 2023, 5, Parts::Part2, false)
+                .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn behaves_like_database() {
+        let dir = TempDir::new().unwrap();
+        let files = FileStorage::new(dir.path());
+        let database = AocDatabase::in_memory().unwrap();
+
+        assert_eq!(exercise(&files), exercise(&database));
+    }
+
+    /// Year, day and whether the input, test input and both answers are stored
+    type Listed = (i32, u8, [bool; 4]);
+
+    /// Clears parts of the stored days and returns the counts and what is still listed
+    fn list_and_clear(storage: &dyn AocStorage) -> ([usize; 3], Vec<Listed>) {
+        exercise(storage);
+        storage.store_input("default", 2023, 6, false, "6").unwrap();
+        storage.store_input("default", 2022, 1, false, "1").unwrap();
+        storage
+            .store_result("default", 2023, 6, Parts::Part1, false, "6")
+            .unwrap();
+
+        let cleared = [
+            storage
+                .clear("default", Some(2023), Some(5), false)
+                .unwrap(),
+            storage.clear("bob", None, None, true).unwrap(),
+            storage.clear("default", Some(2023), Some(6), true).unwrap(),
+        ];
+        let listed = storage
+            .list("default", None)
+            .unwrap()
+            .into_iter()
+            .map(|entry| {
+                let cached = [entry.input, entry.test_input, entry.part_1, entry.part_2];
+                (entry.year, entry.day, cached)
+            })
+            .collect();
+        (cleared, listed)
+    }
+
+    #[test]
+    fn lists_and_clears_like_database() {
+        let dir = TempDir::new().unwrap();
+        let files = FileStorage::new(dir.path());
+        let database = AocDatabase::in_memory().unwrap();
+
+        let (cleared, listed) = list_and_clear(&files);
+        assert_eq!((cleared, listed.clone()), list_and_clear(&database));
+        assert_eq!(cleared, [1, 1, 2]);
+        assert_eq!(
+            listed,
+            [
+                (2022, 1, [true, false, false, false]),
+                (2023, 5, [false, true, false, true]),
+            ]
+        );
+        assert!(files.list("bob", Some(2023)).unwrap()[0].test_input);
+    }
+
+    #[test]
+    fn writes_readable_layout() {
+        let dir = TempDir::new().unwrap();
+        let files = FileStorage::new(dir.path());
+        exercise(&files);
+
+        let day = dir.path().join("2023").join("05");
+        assert_eq!(
+            fs::read_to_string(day.join("input.txt")).unwrap(),
+            "new input"
+        );
+        assert_eq!(
+            fs::read_to_string(day.join("answers.toml")).unwrap(),
+            "part_2 = \"42\"\n"
+        );
+        assert!(day.join("profiles").join("bob").join("input.txt").is_file());
+    }
+}
@@ -0,0 +1,68 @@
+mod encrypted;
+mod files;
+
+pub use encrypted::{is_encrypted, EncryptedStorage};
+pub use files::FileStorage;
+
+use crate::{error::AocError, types::AocCacheEntry, Parts};
+
+/// Backend keeping the inputs and answers of all profiles.
+///
+/// `AocDatabase` passes every call through the backend set with `AocDatabase::with_storage`
+/// and uses its own SQLite tables otherwise. Test inputs and answers are always requested for
+/// the default profile.
+pub trait AocStorage: Send + Sync {
+    /// Gets the input of a day, `None` if it is not stored
+    fn load_input(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        test: bool,
+    ) -> Result<Option<String>, AocError>;
+
+    /// Stores the input of a day, replacing a stored one
+    fn store_input(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        test: bool,
+        input: &str,
+    ) -> Result<(), AocError>;
+
+    /// Gets the correct answer of a part, `None` if it is not stored
+    fn load_result(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        part: Parts,
+        test: bool,
+    ) -> Result<Option<String>, AocError>;
+
+    /// Stores the correct answer of a part, replacing a stored one
+    fn store_result(
+        &self,
+        profile: &str,
+        year: i32,
+        day: u8,
+        part: Parts,
+        test: bool,
+        result: &str,
+    ) -> Result<(), AocError>;
+
+    /// Lists the days with inputs or answers of the profile or test inputs, ordered by year
+    /// and day
+    fn list(&self, profile: &str, year: Option<i32>) -> Result<Vec<AocCacheEntry>, AocError>;
+
+    /// Removes the inputs of the profile, and its answers if `answers` is set. Test cases are
+    /// kept. Returns the number of removed inputs and answer sets
+    fn clear(
+        &self,
+        profile: &str,
+        year: Option<i32>,
+        day: Option<u8>,
+        answers: bool,
+    ) -> Result<usize, AocError>;
+}
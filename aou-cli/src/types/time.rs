@@ -1,14 +1,51 @@
 use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, Utc};
-use std::{fmt::Display, sync::OnceLock, time::Duration};
+use std::{cmp::Ordering, fmt::Display, sync::OnceLock, time::Duration};
 
+use super::AocEvent;
 use crate::error::AocError;
 
-const AOC_START_YEAR: i32 = 2015;
+/// Year of the first Advent of Code
+pub const AOC_START_YEAR: i32 = 2015;
 /// Midnight EST
 const PUZZLE_START_TIME: (u32, u32) = (0, 0);
-const MAX_PUZZLE_DAY: u8 = 25;
 const EST_OFFSET_SECONDS: i32 = -5 * 3600;
 
+/// Clock used by [`AocTime::now`], set at most once per process
+static CLOCK: OnceLock<Box<dyn Clock>> = OnceLock::new();
+
+/// Source of the current time
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The time of the system
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// The system time moved by a fixed offset. Time keeps running from the moment it starts at
+pub struct ShiftedClock {
+    offset: chrono::Duration,
+}
+
+impl ShiftedClock {
+    pub fn starting_at(time: DateTime<Utc>) -> Self {
+        Self {
+            offset: time - Utc::now(),
+        }
+    }
+}
+
+impl Clock for ShiftedClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now() + self.offset
+    }
+}
+
 /// Represents the time configuration for Advent of Code puzzles
 #[derive(Debug, Clone)]
 pub struct AocTime {
@@ -17,18 +54,35 @@ pub struct AocTime {
 }
 
 impl AocTime {
-    /// Creates a new AocTime instance using the current time
+    /// Creates a new AocTime instance using the clock of the process, the system time unless
+    /// [`AocTime::set_clock`] replaced it
     pub fn now() -> Self {
-        static EST_OFFSET: OnceLock<FixedOffset> = OnceLock::new();
-        let est_offset = EST_OFFSET
-            .get_or_init(|| FixedOffset::east_opt(EST_OFFSET_SECONDS).expect("Invalid EST offset"));
+        match CLOCK.get() {
+            Some(clock) => Self::with_clock(clock.as_ref()),
+            None => Self::with_clock(&SystemClock),
+        }
+    }
 
+    /// Creates a new AocTime instance at the time of the clock
+    pub fn with_clock(clock: &dyn Clock) -> Self {
+        Self::at(clock.now())
+    }
+
+    /// Creates a new AocTime instance at the given time
+    pub fn at(time: DateTime<Utc>) -> Self {
+        let est_offset = FixedOffset::east_opt(EST_OFFSET_SECONDS).expect("Invalid EST offset");
         Self {
-            current_time: Utc::now().with_timezone(est_offset),
-            est_offset: *est_offset,
+            current_time: time.with_timezone(&est_offset),
+            est_offset,
         }
     }
 
+    /// Replaces the clock used by [`AocTime::now`] for the rest of the process.
+    /// Returns `false` if a clock was set before
+    pub fn set_clock(clock: impl Clock + 'static) -> bool {
+        CLOCK.set(Box::new(clock)).is_ok()
+    }
+
     /// Gets the current available year for Advent of Code
     pub fn current_year(&self) -> i32 {
         if self.current_time.month() == 12 {
@@ -38,17 +92,33 @@ impl AocTime {
         }
     }
 
+    /// Day of December in EST if the Advent of `current_year` is running
+    fn december_day(&self) -> Option<u8> {
+        match self.current_time.month() {
+            12 => Some(self.current_time.day() as u8),
+            _ => None,
+        }
+    }
+
     /// Gets the maximum available day for a given year
     pub fn available_day(&self, year: i32) -> u8 {
-        match year {
-            y if y > self.current_year() => 0,
-            y if y == self.current_time.year() && self.current_time.month() == 12 => {
-                self.current_time.day() as u8
-            }
-            _ => MAX_PUZZLE_DAY,
+        let days = AocEvent::of(year).days;
+        match year.cmp(&self.current_year()) {
+            Ordering::Greater => 0,
+            Ordering::Equal => self.december_day().map_or(days, |day| day.min(days)),
+            Ordering::Less => days,
         }
     }
 
+    /// Day of the puzzle released today, if the Advent of the year is running
+    pub fn current_day(&self, year: i32) -> Option<u8> {
+        if year != self.current_year() {
+            return None;
+        }
+        self.december_day()
+            .filter(|&day| AocEvent::of(year).has_day(day))
+    }
+
     /// Validates if a given year is valid for Advent of Code
     pub fn validate_year(&self, year: i32) -> Result<(), AocError> {
         match year {
@@ -69,11 +139,12 @@ impl AocTime {
         self.validate_year(year)?;
 
         let max_day = self.available_day(year);
+        let event = AocEvent::of(year);
         match day {
-            d if d == 0 => Err(AocError::InvalidDay {
+            d if !event.has_day(d) => Err(AocError::InvalidDay {
                 year,
                 day,
-                reason: "Day must be between 1 and 25".to_string(),
+                reason: format!("Day must be between 1 and {}", event.days),
             }),
             d if d > max_day => Err(AocError::InvalidDay {
                 year,
@@ -84,6 +155,25 @@ impl AocTime {
         }
     }
 
+    /// Validates if a given date is an Advent of Code puzzle, released or not
+    pub fn validate_upcoming(&self, year: i32, day: u8) -> Result<(), AocError> {
+        if year < AOC_START_YEAR {
+            return Err(AocError::InvalidYear {
+                year,
+                reason: format!("Advent of Code started in {}", AOC_START_YEAR),
+            });
+        }
+        let event = AocEvent::of(year);
+        match event.has_day(day) {
+            true => Ok(()),
+            false => Err(AocError::InvalidDay {
+                year,
+                day,
+                reason: format!("Day must be between 1 and {}", event.days),
+            }),
+        }
+    }
+
     /// Checks if a puzzle is currently available
     pub fn is_puzzle_available(&self, year: i32, day: u8) -> bool {
         self.validate_date(year, day).is_ok()
@@ -96,7 +186,8 @@ impl AocTime {
             chrono::NaiveDate::from_ymd_opt(year, 12, day as u32).expect("Invalid date"),
             chrono::NaiveTime::from_hms_opt(hour, minute, 0).expect("Invalid time"),
         );
-        DateTime::<FixedOffset>::from_naive_utc_and_offset(naive, self.est_offset)
+        // The naive time is midnight in EST, not in UTC
+        DateTime::<FixedOffset>::from_naive_utc_and_offset(naive - self.est_offset, self.est_offset)
     }
 
     /// Gets the time until a puzzle becomes available
@@ -158,3 +249,151 @@ impl Display for AocDuration {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// The given time in EST
+    fn est(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> AocTime {
+        let est = FixedOffset::east_opt(EST_OFFSET_SECONDS).unwrap();
+        let time = est
+            .with_ymd_and_hms(year, month, day, hour, minute, second)
+            .unwrap();
+        AocTime::at(time.with_timezone(&Utc))
+    }
+
+    #[test]
+    fn nothing_released_before_first_minute() {
+        let time = est(2023, 11, 30, 23, 59, 59);
+        assert_eq!(time.current_year(), 2022);
+        assert_eq!(time.available_day(2023), 0);
+        assert_eq!(time.available_day(2022), 25);
+        assert_eq!(time.current_day(2023), None);
+        assert!(time.validate_year(2023).is_err());
+        assert!(!time.is_puzzle_available(2023, 1));
+        assert!(time.validate_upcoming(2023, 1).is_ok());
+        assert_eq!(
+            time.time_until_release(2023, 1),
+            Some(chrono::Duration::seconds(1))
+        );
+    }
+
+    #[test]
+    fn first_puzzle_at_midnight_est() {
+        let time = est(2023, 12, 1, 0, 0, 0);
+        assert_eq!(time.current_year(), 2023);
+        assert_eq!(time.available_day(2023), 1);
+        assert_eq!(time.current_day(2023), Some(1));
+        assert!(time.is_puzzle_available(2023, 1));
+        assert!(!time.is_puzzle_available(2023, 2));
+        assert_eq!(time.time_until_release(2023, 1), None);
+        assert_eq!(
+            time.time_until_release(2023, 2),
+            Some(chrono::Duration::days(1))
+        );
+        assert_eq!(
+            time.puzzle_release_time(2023, 1),
+            Utc.with_ymd_and_hms(2023, 12, 1, 5, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn utc_midnight_is_still_previous_day() {
+        let time = AocTime::at(Utc.with_ymd_and_hms(2023, 12, 1, 4, 59, 59).unwrap());
+        assert_eq!(time.available_day(2023), 0);
+
+        let time = AocTime::at(Utc.with_ymd_and_hms(2023, 12, 10, 4, 59, 59).unwrap());
+        assert_eq!(time.available_day(2023), 9);
+        assert_eq!(time.current_day(2023), Some(9));
+        assert!(!time.is_puzzle_available(2023, 10));
+    }
+
+    #[test]
+    fn last_days_of_december() {
+        let time = est(2023, 12, 25, 0, 0, 0);
+        assert_eq!(time.available_day(2023), 25);
+        assert_eq!(time.current_day(2023), Some(25));
+
+        let time = est(2023, 12, 31, 23, 59, 59);
+        assert_eq!(time.available_day(2023), 25);
+        assert_eq!(time.current_day(2023), None);
+        assert!(time.validate_date(2023, 26).is_err());
+    }
+
+    #[test]
+    fn twelve_day_calendar_ends_early() {
+        let time = est(2025, 12, 12, 0, 0, 0);
+        assert_eq!(time.available_day(2025), 12);
+        assert_eq!(time.current_day(2025), Some(12));
+
+        let time = est(2025, 12, 13, 0, 0, 0);
+        assert_eq!(time.available_day(2025), 12);
+        assert_eq!(time.current_day(2025), None);
+
+        let error = time.validate_date(2025, 13).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid day 13 for year 2025: Day must be between 1 and 12"
+        );
+    }
+
+    #[test]
+    fn january_keeps_previous_advent() {
+        let time = est(2024, 1, 1, 0, 0, 0);
+        assert_eq!(time.current_year(), 2023);
+        assert_eq!(time.available_day(2023), 25);
+        assert_eq!(time.available_day(2024), 0);
+        assert_eq!(time.current_day(2023), None);
+        assert_eq!(time.current_day(2024), None);
+        assert!(time.validate_date(2023, 25).is_ok());
+        assert!(time.validate_year(2024).is_err());
+        assert!(time.validate_upcoming(2024, 1).is_ok());
+    }
+
+    #[test]
+    fn future_years_are_unavailable() {
+        let time = est(2023, 12, 15, 12, 0, 0);
+        for year in 2024..=2030 {
+            assert_eq!(time.available_day(year), 0, "{year}");
+            assert!(time.validate_year(year).is_err(), "{year}");
+            assert!(!time.is_puzzle_available(year, 1), "{year}");
+            assert!(time.validate_upcoming(year, 1).is_ok(), "{year}");
+        }
+        assert!(time.validate_upcoming(2030, 13).is_err());
+        assert!(time.validate_upcoming(2024, 25).is_ok());
+    }
+
+    #[test]
+    fn past_years_follow_their_calendar() {
+        let time = est(2026, 6, 1, 0, 0, 0);
+        for year in 2015..=2024 {
+            assert_eq!(time.available_day(year), 25, "{year}");
+            assert!(time.validate_date(year, 25).is_ok(), "{year}");
+            assert!(time.validate_date(year, 26).is_err(), "{year}");
+            assert!(time.validate_upcoming(year, 26).is_err(), "{year}");
+            assert_eq!(time.current_day(year), None, "{year}");
+        }
+        assert_eq!(time.available_day(2025), 12);
+        assert!(time.validate_year(2014).is_err());
+        assert!(time.validate_upcoming(2014, 1).is_err());
+    }
+
+    #[test]
+    fn rejects_day_zero() {
+        let time = est(2023, 12, 15, 0, 0, 0);
+        assert!(time.validate_date(2015, 0).is_err());
+        assert!(time.validate_date(2023, 0).is_err());
+        assert!(time.validate_upcoming(2025, 0).is_err());
+    }
+
+    #[test]
+    fn shifted_clock_keeps_running() {
+        let start = Utc.with_ymd_and_hms(2023, 12, 1, 5, 0, 0).unwrap();
+        let clock = ShiftedClock::starting_at(start);
+        let elapsed = clock.now() - start;
+        assert!(elapsed >= chrono::Duration::zero() && elapsed < chrono::Duration::seconds(5));
+        assert_eq!(AocTime::with_clock(&clock).current_day(2023), Some(1));
+    }
+}
@@ -1,10 +1,16 @@
-use quote::ToTokens;
 use syn::punctuated::Punctuated;
 
+/// First year with the shortened calendar of 12 days, like `AocEvent` of the CLI
+const SHORT_CALENDAR_YEAR: i32 = 2025;
+const FIRST_YEAR: i32 = 2015;
+
 #[proc_macro]
 pub fn add_days(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let exprs = syn::parse_macro_input!(input with Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated);
-    let numbers = evaluate_expr(exprs);
+    let numbers = match evaluate_expr(exprs, Calendar::of_crate()) {
+        Ok(numbers) => numbers,
+        Err(error) => return error.to_compile_error().into(),
+    };
 
     let mut modules: Vec<proc_macro2::TokenStream> = Vec::new();
     let mut uses: Vec<proc_macro2::TokenStream> = Vec::new();
@@ -65,31 +71,151 @@ pub fn add_days(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     proc_macro::TokenStream::from(expanded)
 }
 
-fn evaluate_expr(input: Punctuated<syn::Expr, syn::token::Comma>) -> Vec<u8> {
+/// Year in the name of the crate being compiled, e.g. 2023 for `aoc2023`
+fn crate_year() -> Option<i32> {
+    let name = std::env::var("CARGO_CRATE_NAME").ok()?;
+    name.split(|c: char| !c.is_ascii_digit())
+        .filter(|digits| digits.len() == 4)
+        .filter_map(|digits| digits.parse().ok())
+        .find(|year| *year >= FIRST_YEAR)
+}
+
+/// The days of the year of the crate, as many as any year had if it is unknown
+#[derive(Clone, Copy)]
+struct Calendar {
+    year: Option<i32>,
+    days: u8,
+}
+
+impl Calendar {
+    fn of_crate() -> Self {
+        Self::of_year(crate_year())
+    }
+
+    fn of_year(year: Option<i32>) -> Self {
+        match year {
+            Some(year) if year >= SHORT_CALENDAR_YEAR => Self {
+                year: Some(year),
+                days: 12,
+            },
+            year => Self { year, days: 25 },
+        }
+    }
+}
+
+fn parse_day(expr: &syn::Expr, calendar: Calendar) -> syn::Result<u8> {
+    let day = match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(lit),
+            ..
+        }) => lit.base10_parse::<u8>()?,
+        _ => return Err(syn::Error::new_spanned(expr, "Expected a day number")),
+    };
+    if !(1..=calendar.days).contains(&day) {
+        let event = match calendar.year {
+            Some(year) => format!("Advent of Code {year}"),
+            None => "Advent of Code".to_string(),
+        };
+        return Err(syn::Error::new_spanned(
+            expr,
+            format!("{event} has days 1 to {}, not {day}", calendar.days),
+        ));
+    }
+    Ok(day)
+}
+
+fn evaluate_expr(
+    input: Punctuated<syn::Expr, syn::token::Comma>,
+    calendar: Calendar,
+) -> syn::Result<Vec<u8>> {
     let mut numbers = Vec::new();
     for expr in input {
-        match expr {
+        match &expr {
             syn::Expr::Range(syn::ExprRange {
-                start,
-                limits: _,
-                end,
+                start: Some(start),
+                limits,
+                end: Some(end),
                 ..
             }) => {
-                if let (Some(start), Some(end)) = (start, end) {
-                    // Extract start and end values
-                    if let (syn::Expr::Lit(start), syn::Expr::Lit(end)) = (*start, *end) {
-                        let start_num = start.to_token_stream().to_string().parse::<u8>().unwrap();
-                        let end_num = end.to_token_stream().to_string().parse::<u8>().unwrap();
-                        numbers.extend(start_num..=end_num);
-                    }
+                let start_num = parse_day(start, calendar)?;
+                let end_num = parse_day(end, calendar)?;
+                // Before 0.3 `a..b` included `b`, so it is rejected instead of dropping a day
+                if let syn::RangeLimits::HalfOpen(_) = limits {
+                    return Err(syn::Error::new_spanned(
+                        &expr,
+                        format!(
+                            "Ranges of days include their end, write `{start_num}..={end_num}`"
+                        ),
+                    ));
                 }
+                numbers.extend(start_num..=end_num);
             }
-            syn::Expr::Lit(lit) => {
-                let num = lit.to_token_stream().to_string().parse::<u8>().unwrap();
-                numbers.push(num);
+            syn::Expr::Range(_) => {
+                return Err(syn::Error::new_spanned(
+                    expr,
+                    "Ranges of days need a start and an end",
+                ))
             }
-            _ => {}
+            _ => numbers.push(parse_day(&expr, calendar)?),
         }
     }
-    numbers
+    Ok(numbers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse::Parser;
+
+    fn days(input: &str, year: i32) -> syn::Result<Vec<u8>> {
+        let exprs = Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated
+            .parse_str(input)
+            .unwrap();
+        evaluate_expr(exprs, Calendar::of_year(Some(year)))
+    }
+
+    fn error(input: &str, year: i32) -> String {
+        days(input, year).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn ranges_include_their_end() {
+        assert_eq!(days("1..=3, 5", 2015).unwrap(), [1, 2, 3, 5]);
+        assert_eq!(days("1..=25", 2024).unwrap(), (1..=25).collect::<Vec<_>>());
+        assert_eq!(days("1..=12", 2025).unwrap(), (1..=12).collect::<Vec<_>>());
+        assert_eq!(
+            error("1..10", 2024),
+            "Ranges of days include their end, write `1..=10`"
+        );
+    }
+
+    #[test]
+    fn rejects_days_outside_the_calendar() {
+        assert_eq!(
+            error("1..=13", 2025),
+            "Advent of Code 2025 has days 1 to 12, not 13"
+        );
+        assert_eq!(
+            error("1..13", 2025),
+            "Advent of Code 2025 has days 1 to 12, not 13"
+        );
+        assert_eq!(
+            error("26", 2015),
+            "Advent of Code 2015 has days 1 to 25, not 26"
+        );
+        assert!(days("0", 2024).is_err());
+        assert!(days("25", 2024).is_ok());
+    }
+
+    #[test]
+    fn rejects_open_ranges_and_expressions() {
+        assert_eq!(error("5..", 2024), "Ranges of days need a start and an end");
+        assert_eq!(
+            error("..=5", 2024),
+            "Ranges of days need a start and an end"
+        );
+        assert_eq!(error("DAY", 2024), "Expected a day number");
+        assert_eq!(error("1 + 1", 2024), "Expected a day number");
+        assert_eq!(error("1..=last", 2024), "Expected a day number");
+    }
 }
@@ -1 +1 @@
-advent_of_utils::add_days!();;
+advent_of_utils::add_days!();
@@ -25,7 +25,7 @@
 Add this to your `Cargo.toml`:
 ```toml
 [dependencies]
-advent-of-utils = "0.2.0"
+advent-of-utils = "0.3.0"
 
 [lib]
 crate-type = ["cdylib"]
@@ -66,10 +66,14 @@ cd aoc-2023
 In your project's `lib.rs`, use the `add_days!` macro to generate the boilerplate for your solutions:
 
 ```rust
+# #[path = "../examples/year_example"]
+# mod solutions {
 use advent_of_utils::add_days;
 
-// Generate modules for days 1 through 25
-add_days!(1..=25);
+// Generate modules for days 1 and 2, like in `examples/year_example`
+add_days!(1..=2);
+# }
+# fn main() {}
 ```
 
 ## 3. Implement Solutions
@@ -129,7 +133,12 @@ mod solution;
 ///
 /// # Arguments
 ///
-/// * Takes a comma-separated list of expressions representing the day numbers (e.g., `add_days!(1..10, 12, 13)`)
+/// * Takes a comma-separated list of expressions representing the day numbers (e.g., `add_days!(1..=10, 12, 13)`)
+///
+/// Ranges include their end and have to be written as `a..=b`, `a..b` is a compile error.
+///
+/// Days outside the calendar are a compile error. The year is read from the crate name, which
+/// limits crates like `aoc2025` to the 12 days of 2025 and later, all others to 25 days.
 ///
 /// # Generated Code
 ///
@@ -141,11 +150,16 @@ mod solution;
 /// # Example
 ///
 /// ```rust
-/// add_days!(1, 2, 15);
+/// # #[path = "../examples/year_example"]
+/// # mod solutions {
+/// # use advent_of_utils::add_days;
+/// add_days!(1, 2);
+/// # }
+/// # fn main() {}
 /// ```
 ///
 /// This will generate:
-/// - Modules: `mod day01;`, `mod day02;`, `mod day15;`
+/// - Modules: `mod day01;`, `mod day02;`
 /// - Solution mapping in a HashMap
 pub use advent_of_utils_macros::add_days;
 pub use options::AocOption;
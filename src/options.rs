@@ -22,6 +22,7 @@ use std::num::TryFromIntError;
 ///
 /// # Example
 /// ```rust
+/// # use advent_of_utils::AocOption;
 /// let string_opt: AocOption = "puzzle input".to_string().into();   // Creates AocOption::Str
 /// let num_opt: AocOption = 42_i32.into();                          // Creates AocOption::Int
 /// let some_opt: AocOption = Some(42_i32).into();                   // Creates AocOption::Int
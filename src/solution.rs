@@ -4,6 +4,10 @@ use crate::AocOption;
 /// This trait must be implemented for each day's solution, providing methods
 /// to solve both parts of the puzzle.
 ///
+/// Solutions have to be `Send + Sync` so the CLI can run several days in parallel and
+/// give up on a part that exceeds its timeout.
+/// Unit structs like in the example below fulfill this automatically.
+///
 /// # Example
 /// ```rust
 /// use advent_of_utils::{Solution, AocOption};
@@ -22,12 +26,12 @@ use crate::AocOption;
 ///     }
 /// }
 /// ```
-pub trait Solution {
+pub trait Solution: Send + Sync {
     /// Solves Part 1 of the daily puzzle.
     ///
     /// # Arguments
     /// * `input` - The puzzle input as a string, automatically fetched and cached
-    ///             from Advent of Code.
+    ///   from Advent of Code.
     ///
     /// # Returns
     /// * `AocOption` - The solution result, which can be either:
@@ -43,7 +47,7 @@ pub trait Solution {
     ///
     /// # Arguments
     /// * `input` - The puzzle input as a string, automatically fetched and cached
-    ///             from Advent of Code.
+    ///   from Advent of Code.
     ///
     /// # Returns
     /// * `AocOption` - The solution result, which can be either: